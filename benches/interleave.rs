@@ -0,0 +1,68 @@
+// Measures the throughput of the pixel preprocessing on the host machine.
+//
+// Run with:
+//     cargo bench --bench interleave --target x86_64-unknown-linux-gnu
+//
+// Note that the absolute numbers differ greatly from the ones on the
+// actual microcontroller; this is only meant to compare the
+// different code paths with each other.
+
+use std::{hint::black_box, time::Instant};
+
+use ws2812_flexio::{IntoPixelStream, PreprocessedPixels};
+
+const NUM_PIXELS: usize = 4000;
+const ITERATIONS: u32 = 200;
+
+fn report(name: &str, bytes_per_iteration: usize, run: impl FnMut()) {
+    let mut run = run;
+
+    // Warmup
+    run();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    let elapsed = start.elapsed();
+
+    let bytes = bytes_per_iteration as f64 * f64::from(ITERATIONS);
+    let throughput = bytes / (elapsed.as_secs_f64() * 1_000_000.0);
+
+    println!("{name:<24} {throughput:>10.1} bytes/us");
+}
+
+macro_rules! bench_strips {
+    ($frame:expr, $l:literal, $($strip:ident)+) => {{
+        let frame: &[[u8; 3]] = $frame;
+        let mut buffer = Box::new(PreprocessedPixels::<NUM_PIXELS, $l>::new());
+        let bytes = frame.len() * 3 * $l;
+
+        report(concat!("prepare_pixels (L=", $l, ")"), bytes, || {
+            $(let mut $strip = frame.iter().into_pixel_stream();)+
+            black_box(buffer.prepare_pixels([$(&mut $strip),+]));
+        });
+
+        report(concat!("prepare_mirrored (L=", $l, ")"), bytes, || {
+            let mut stream = frame.iter().into_pixel_stream();
+            black_box(buffer.prepare_mirrored(&mut stream, [true; $l]));
+        });
+
+        let raw: Vec<u8> = frame.iter().flatten().copied().collect();
+        let raw = raw.as_slice();
+        report(concat!("prepare_bytes (L=", $l, ")"), bytes, || {
+            black_box(buffer.prepare_bytes([raw; $l]));
+        });
+    }};
+}
+
+fn main() {
+    let frame: Vec<[u8; 3]> = (0..NUM_PIXELS)
+        .map(|i| [i as u8, (i >> 2) as u8, (i >> 4) as u8])
+        .collect();
+
+    bench_strips!(&frame, 1, a);
+    bench_strips!(&frame, 2, a b);
+    bench_strips!(&frame, 3, a b c);
+    bench_strips!(&frame, 4, a b c d);
+}
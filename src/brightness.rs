@@ -0,0 +1,47 @@
+/// A brightness setting, from `0` (off) to `255` (full brightness).
+///
+/// The scaling is done in integer math. Channels that are not completely
+/// off stay on at their lowest value instead of being rounded down to zero,
+/// so dim colors don't disappear or change their hue when dimmed further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Brightness(u8);
+
+impl Brightness {
+    /// Full brightness; values pass through unchanged.
+    pub const FULL: Self = Self(255);
+    /// Zero brightness; all values are turned off.
+    pub const OFF: Self = Self(0);
+
+    /// Creates a new brightness setting.
+    pub const fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// The raw brightness value.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Scales a single channel value by this brightness.
+    pub const fn scale(self, value: u8) -> u8 {
+        let scaled = ((value as u16 * (self.0 as u16 + 1)) >> 8) as u8;
+
+        if scaled == 0 && value != 0 && self.0 != 0 {
+            1
+        } else {
+            scaled
+        }
+    }
+}
+
+impl Default for Brightness {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl From<u8> for Brightness {
+    fn from(value: u8) -> Self {
+        Self::new(value)
+    }
+}
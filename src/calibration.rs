@@ -0,0 +1,322 @@
+use crate::{pixel::Pixel, rgbw::WhitePoint};
+
+/// The number of fractional bits of the calibration matrix.
+const FRACTIONAL_BITS: u32 = 12;
+/// The fixed point representation of `1.0` in the calibration matrix.
+const ONE: i32 = 1 << FRACTIONAL_BITS;
+
+/// A color calibration of an LED strip.
+///
+/// Consists of a 3x3 matrix that maps the requested RGB color to the
+/// RGB color that gets sent to the strip. This allows strips of different
+/// batches to be matched to each other.
+///
+/// This is a plain data struct, so it can be persisted, for example in flash,
+/// and loaded at boot; see [`to_le_bytes()`](Calibration::to_le_bytes) and
+/// [`from_le_bytes()`](Calibration::from_le_bytes).
+///
+/// Calibrations get applied to pixels by wrapping them in [`Calibrated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Calibration {
+    /// The color correction matrix, with 12 fractional bits (`4096` represents `1.0`).
+    ///
+    /// The rows are the output channels and the columns the input channels,
+    /// both in RGB order.
+    pub matrix: [[i16; 3]; 3],
+}
+
+impl Calibration {
+    /// The size of the serialized calibration, in bytes.
+    pub const SERIALIZED_SIZE: usize = 18;
+
+    /// A calibration that doesn't modify the colors.
+    pub const IDENTITY: Self = Self::from_gains(ONE as u16, ONE as u16, ONE as u16);
+
+    /// Creates a calibration from a color correction matrix.
+    ///
+    /// See [`matrix`](Calibration::matrix) for the format.
+    pub const fn from_matrix(matrix: [[i16; 3]; 3]) -> Self {
+        Self { matrix }
+    }
+
+    /// Creates a calibration that scales each channel individually.
+    ///
+    /// The gains have 12 fractional bits; `4096` represents `1.0`.
+    /// Gains above `1.0` are possible, but will clip bright colors.
+    pub const fn from_gains(red: u16, green: u16, blue: u16) -> Self {
+        let gains = [red, green, blue];
+
+        let mut matrix = [[0; 3]; 3];
+        let mut channel = 0;
+        while channel < 3 {
+            let gain = gains[channel];
+            matrix[channel][channel] = if gain > i16::MAX as u16 {
+                i16::MAX
+            } else {
+                gain as i16
+            };
+            channel += 1;
+        }
+
+        Self { matrix }
+    }
+
+    /// Creates a white balancing calibration.
+    ///
+    /// # Arguments
+    ///
+    /// * `native` - The color of the strip when showing full white
+    /// * `target` - The color that full white should have instead
+    ///
+    /// The gains are normalized so that no channel exceeds `1.0`,
+    /// meaning that white balancing never clips.
+    pub const fn white_balance(native: WhitePoint, target: WhitePoint) -> Self {
+        let native = [native.red, native.green, native.blue];
+        let target = [target.red, target.green, target.blue];
+
+        let mut gains = [0; 3];
+        let mut max_gain = 0;
+        let mut channel = 0;
+        while channel < 3 {
+            gains[channel] = if native[channel] == 0 {
+                0
+            } else {
+                target[channel] as u32 * ONE as u32 / native[channel] as u32
+            };
+            if gains[channel] > max_gain {
+                max_gain = gains[channel];
+            }
+            channel += 1;
+        }
+
+        if max_gain == 0 {
+            return Self::from_gains(0, 0, 0);
+        }
+
+        let mut channel = 0;
+        while channel < 3 {
+            gains[channel] = gains[channel] * ONE as u32 / max_gain;
+            channel += 1;
+        }
+
+        Self::from_gains(gains[0] as u16, gains[1] as u16, gains[2] as u16)
+    }
+
+    /// Creates a white balancing calibration from color temperatures.
+    ///
+    /// # Arguments
+    ///
+    /// * `native_kelvin` - The color temperature of the strip when showing full white
+    /// * `target_kelvin` - The color temperature that full white should have instead
+    pub const fn white_balance_kelvin(native_kelvin: u16, target_kelvin: u16) -> Self {
+        Self::white_balance(
+            WhitePoint::from_kelvin(native_kelvin),
+            WhitePoint::from_kelvin(target_kelvin),
+        )
+    }
+
+    /// Combines two calibrations; the result applies `self` first, then `next`.
+    pub const fn then(self, next: Self) -> Self {
+        let mut matrix = [[0; 3]; 3];
+
+        let mut row = 0;
+        while row < 3 {
+            let mut column = 0;
+            while column < 3 {
+                // Three products of two `i16` can exceed the range of `i32`
+                let mut sum: i64 = 0;
+                let mut k = 0;
+                while k < 3 {
+                    sum += next.matrix[row][k] as i64 * self.matrix[k][column] as i64;
+                    k += 1;
+                }
+                sum = (sum + ONE as i64 / 2) >> FRACTIONAL_BITS;
+                matrix[row][column] = if sum > i16::MAX as i64 {
+                    i16::MAX
+                } else if sum < i16::MIN as i64 {
+                    i16::MIN
+                } else {
+                    sum as i16
+                };
+                column += 1;
+            }
+            row += 1;
+        }
+
+        Self { matrix }
+    }
+
+    /// Applies the calibration to an RGB color.
+    pub fn apply(&self, rgb: [u8; 3]) -> [u8; 3] {
+        let mut result = [0; 3];
+        for (out, row) in result.iter_mut().zip(&self.matrix) {
+            let sum: i32 = row
+                .iter()
+                .zip(rgb)
+                .map(|(&factor, value)| i32::from(factor) * i32::from(value))
+                .sum();
+            *out = ((sum + ONE / 2) >> FRACTIONAL_BITS).clamp(0, 255) as u8;
+        }
+        result
+    }
+
+    /// Serializes the calibration, for example to store it in flash.
+    pub fn to_le_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut bytes = [0; Self::SERIALIZED_SIZE];
+        for (chunk, value) in bytes.chunks_exact_mut(2).zip(self.matrix.iter().flatten()) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a calibration that was serialized with [`to_le_bytes()`](Calibration::to_le_bytes).
+    pub fn from_le_bytes(bytes: [u8; Self::SERIALIZED_SIZE]) -> Self {
+        let mut matrix = [[0; 3]; 3];
+        for (value, chunk) in matrix.iter_mut().flatten().zip(bytes.chunks_exact(2)) {
+            *value = i16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+        Self { matrix }
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// A pixel that gets color calibrated before transmission.
+///
+/// The calibration gets applied to the first three bytes of the pixel,
+/// which are expected to be in GRB order; additional channels, like
+/// the white channel of RGBW pixels, stay untouched.
+///
+/// As every strip gets its own pixel stream, every strip can have its own calibration:
+///
+/// ```rust
+/// static CALIBRATIONS: [Calibration; 3] = /* loaded from flash */;
+///
+/// buffer.prepare_pixels([
+///     &mut fb_0.iter().map(|p| Calibrated::new(p, &CALIBRATIONS[0])).into_pixel_stream(),
+///     &mut fb_1.iter().map(|p| Calibrated::new(p, &CALIBRATIONS[1])).into_pixel_stream(),
+///     &mut fb_2.iter().map(|p| Calibrated::new(p, &CALIBRATIONS[2])).into_pixel_stream(),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Calibrated<'a, P> {
+    pixel: P,
+    calibration: &'a Calibration,
+}
+
+impl<'a, P: Pixel> Calibrated<'a, P> {
+    /// Wraps a pixel to apply the given calibration.
+    pub fn new(pixel: P, calibration: &'a Calibration) -> Self {
+        Self { pixel, calibration }
+    }
+}
+
+impl<P: Pixel> Pixel for Calibrated<'_, P> {
+    const BYTES: usize = P::BYTES;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        self.pixel.write_bytes(out);
+
+        if let [green, red, blue, ..] = out {
+            [*red, *green, *blue] = self.calibration.apply([*red, *green, *blue]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_matrix() {
+        assert_eq!(Calibration::IDENTITY.apply([1, 128, 255]), [1, 128, 255]);
+
+        let half_red = Calibration::from_gains(ONE as u16 / 2, ONE as u16, ONE as u16);
+        assert_eq!(half_red.apply([200, 100, 50]), [100, 100, 50]);
+
+        // Mixes red into green, clips to the valid range
+        let mut matrix = Calibration::IDENTITY.matrix;
+        matrix[1][0] = ONE as i16;
+        matrix[2][2] = -(ONE as i16);
+        let calibration = Calibration::from_matrix(matrix);
+        assert_eq!(calibration.apply([200, 100, 50]), [200, 255, 0]);
+    }
+
+    #[test]
+    fn then_applies_both() {
+        let first = Calibration::from_gains(ONE as u16 / 2, ONE as u16, 2 * ONE as u16);
+        let second = Calibration::from_gains(ONE as u16, ONE as u16 / 4, ONE as u16 / 2);
+        let combined = first.then(second);
+
+        assert_eq!(
+            combined,
+            Calibration::from_gains(ONE as u16 / 2, ONE as u16 / 4, ONE as u16)
+        );
+        let rgb = [200, 100, 50];
+        assert_eq!(combined.apply(rgb), second.apply(first.apply(rgb)));
+        assert_eq!(Calibration::IDENTITY.then(first), first);
+    }
+
+    #[test]
+    fn then_saturates_instead_of_overflowing() {
+        let large = Calibration::from_matrix([[i16::MAX; 3]; 3]);
+
+        assert_eq!(
+            large.then(large),
+            Calibration::from_matrix([[i16::MAX; 3]; 3])
+        );
+
+        let mut negative = [[i16::MAX; 3]; 3];
+        negative[0] = [i16::MIN; 3];
+        assert_eq!(
+            large.then(Calibration::from_matrix(negative)).matrix[0],
+            [i16::MIN; 3]
+        );
+    }
+
+    #[test]
+    fn white_balance() {
+        let target = WhitePoint::from_kelvin(3000);
+        let calibration = Calibration::white_balance(WhitePoint::NEUTRAL, target);
+
+        let [red, green, blue] = calibration.apply([255, 255, 255]);
+        assert!(red.abs_diff(target.red) <= 1);
+        assert!(green.abs_diff(target.green) <= 1);
+        assert!(blue.abs_diff(target.blue) <= 1);
+
+        assert_eq!(
+            Calibration::white_balance_kelvin(6500, 3000),
+            Calibration::white_balance(WhitePoint::COOL_6500K, WhitePoint::WARM_3000K)
+        );
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let calibration =
+            Calibration::from_matrix([[1, -2, 3], [i16::MIN, 5, 6], [7, 8, i16::MAX]]);
+        assert_eq!(
+            Calibration::from_le_bytes(calibration.to_le_bytes()),
+            calibration
+        );
+    }
+
+    #[test]
+    fn calibrated_pixels() {
+        let calibration = Calibration::from_gains(ONE as u16 / 2, ONE as u16, 0);
+
+        // `[u8; 3]` is RGB, gets transmitted as GRB
+        let mut out = [0; 3];
+        Calibrated::new([200u8, 100, 50], &calibration).write_bytes(&mut out);
+        assert_eq!(out, [100, 100, 0]);
+
+        // The white channel stays untouched
+        let mut out = [0; 4];
+        Calibrated::new([100u8, 200, 50, 77], &calibration).write_bytes(&mut out);
+        assert_eq!(out, [100, 100, 0, 77]);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::pixel::Pixel;
+
+/// The order in which an LED strip expects its color channels.
+///
+/// All pixel types of this crate produce GRB (or GRBW) data, which is
+/// what WS2812 and SK6812 strips expect; the only exception is `[u8; 4]`,
+/// whose bytes get transmitted unchanged. Strips with a different
+/// channel order can be driven by wrapping the pixels in [`Reordered`].
+///
+/// Three-channel orders only rearrange the first three bytes of a pixel,
+/// all further bytes stay untouched. Four-channel orders are only applied
+/// to pixels with at least four bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorOrder {
+    /// Red, green, blue
+    Rgb,
+    /// Red, blue, green
+    Rbg,
+    /// Green, red, blue
+    #[default]
+    Grb,
+    /// Green, blue, red
+    Gbr,
+    /// Blue, red, green
+    Brg,
+    /// Blue, green, red
+    Bgr,
+    /// Red, green, blue, white
+    Rgbw,
+    /// Red, blue, green, white
+    Rbgw,
+    /// Green, red, blue, white
+    Grbw,
+    /// Green, blue, red, white
+    Gbrw,
+    /// Blue, red, green, white
+    Brgw,
+    /// Blue, green, red, white
+    Bgrw,
+}
+
+impl ColorOrder {
+    /// For every output byte, the position of the corresponding byte in GRB(W) data.
+    const fn permutation(self) -> &'static [usize] {
+        const G: usize = 0;
+        const R: usize = 1;
+        const B: usize = 2;
+        const W: usize = 3;
+
+        match self {
+            ColorOrder::Rgb => &[R, G, B],
+            ColorOrder::Rbg => &[R, B, G],
+            ColorOrder::Grb => &[G, R, B],
+            ColorOrder::Gbr => &[G, B, R],
+            ColorOrder::Brg => &[B, R, G],
+            ColorOrder::Bgr => &[B, G, R],
+            ColorOrder::Rgbw => &[R, G, B, W],
+            ColorOrder::Rbgw => &[R, B, G, W],
+            ColorOrder::Grbw => &[G, R, B, W],
+            ColorOrder::Gbrw => &[G, B, R, W],
+            ColorOrder::Brgw => &[B, R, G, W],
+            ColorOrder::Bgrw => &[B, G, R, W],
+        }
+    }
+
+    /// Rearranges the GRB(W) encoded bytes of a single pixel into this color order.
+    pub fn apply(self, bytes: &mut [u8]) {
+        let permutation = self.permutation();
+        if permutation.len() > bytes.len() {
+            return;
+        }
+
+        let mut original = [0; 4];
+        original[..permutation.len()].copy_from_slice(&bytes[..permutation.len()]);
+
+        for (byte, &source) in bytes.iter_mut().zip(permutation) {
+            *byte = original[source];
+        }
+    }
+}
+
+/// A pixel that gets transmitted in a different [`ColorOrder`].
+///
+/// The bytes of the wrapped pixel are interpreted as GRB(W). Raw `[u8; 4]` data,
+/// which is otherwise transmitted unchanged, therefore has to be in GRBW order.
+///
+/// As every strip gets its own pixel stream, the order can be chosen per strip:
+///
+/// ```rust
+/// buffer.prepare_pixels([
+///     &mut framebuffer_0.iter().into_pixel_stream(),
+///     &mut framebuffer_1
+///         .iter()
+///         .map(|pixel| Reordered::new(pixel, ColorOrder::Brg))
+///         .into_pixel_stream(),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reordered<P> {
+    pixel: P,
+    order: ColorOrder,
+}
+
+impl<P: Pixel> Reordered<P> {
+    /// Wraps a pixel to transmit it in the given color order.
+    pub const fn new(pixel: P, order: ColorOrder) -> Self {
+        Self { pixel, order }
+    }
+}
+
+impl<P: Pixel> Pixel for Reordered<P> {
+    const BYTES: usize = P::BYTES;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        self.pixel.write_bytes(out);
+        self.order.apply(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reorder(order: ColorOrder, bytes: &[u8]) -> [u8; 5] {
+        let mut out = [0; 5];
+        out[..bytes.len()].copy_from_slice(bytes);
+        order.apply(&mut out[..bytes.len()]);
+        out
+    }
+
+    #[test]
+    fn three_channel_orders() {
+        let grb = [b'G', b'R', b'B'];
+        for (order, expected) in [
+            (ColorOrder::Rgb, b"RGB"),
+            (ColorOrder::Rbg, b"RBG"),
+            (ColorOrder::Grb, b"GRB"),
+            (ColorOrder::Gbr, b"GBR"),
+            (ColorOrder::Brg, b"BRG"),
+            (ColorOrder::Bgr, b"BGR"),
+        ] {
+            assert_eq!(reorder(order, &grb)[..3], expected[..], "{order:?}");
+        }
+
+        // Further bytes stay untouched
+        assert_eq!(reorder(ColorOrder::Bgr, b"GRBWX"), *b"BGRWX");
+    }
+
+    #[test]
+    fn four_channel_orders() {
+        let grbw = [b'G', b'R', b'B', b'W'];
+        for (order, expected) in [
+            (ColorOrder::Rgbw, b"RGBW"),
+            (ColorOrder::Rbgw, b"RBGW"),
+            (ColorOrder::Grbw, b"GRBW"),
+            (ColorOrder::Gbrw, b"GBRW"),
+            (ColorOrder::Brgw, b"BRGW"),
+            (ColorOrder::Bgrw, b"BGRW"),
+        ] {
+            assert_eq!(reorder(order, &grbw)[..4], expected[..], "{order:?}");
+        }
+
+        // Not applied to pixels with less than four bytes
+        assert_eq!(reorder(ColorOrder::Rgbw, b"GRB")[..3], *b"GRB");
+    }
+
+    #[test]
+    fn reordered_pixels() {
+        let mut out = [0; 3];
+        Reordered::new([1u8, 2, 3], ColorOrder::Bgr).write_bytes(&mut out);
+        assert_eq!(out, [3, 2, 1]);
+
+        let mut out = [0; 4];
+        Reordered::new([b'G', b'R', b'B', b'W'], ColorOrder::Rgbw).write_bytes(&mut out);
+        assert_eq!(out, *b"RGBW");
+    }
+}
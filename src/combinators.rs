@@ -0,0 +1,288 @@
+use crate::pixelstream::PixelStreamRef;
+
+/// Combinators for common strip plumbing, available on all pixel streams.
+///
+/// All combinators work directly on the byte stream, so no intermediate
+/// buffers are required. All lengths are given in pixels; added pixels are black.
+///
+/// Pixel based conversions are available directly on [`PixelStream`](crate::PixelStream),
+/// see [`map()`](crate::PixelStream::map) and [`reverse()`](crate::PixelStream::reverse).
+///
+/// ```rust
+/// // The first 10 LEDs are hidden behind a cover, and the strip
+/// // is longer than the animation
+/// let mut stream = framebuffer
+///     .iter()
+///     .into_pixel_stream()
+///     .offset(10)
+///     .pad(NUM_PIXELS);
+/// ```
+pub trait PixelStreamExt: PixelStreamRef + Sized {
+    /// Prepends the given number of black pixels, shifting the stream along the strip.
+    fn offset(self, pixels: usize) -> Offset<Self> {
+        let remaining = pixels * self.bytes_per_pixel();
+        Offset {
+            stream: self,
+            remaining,
+        }
+    }
+
+    /// Skips the given number of leading pixels.
+    fn skip_pixels(self, pixels: usize) -> SkipPixels<Self> {
+        let remaining = pixels * self.bytes_per_pixel();
+        SkipPixels {
+            stream: self,
+            remaining,
+        }
+    }
+
+    /// Limits the stream to the given number of pixels.
+    fn take_pixels(self, pixels: usize) -> TakePixels<Self> {
+        let remaining = pixels * self.bytes_per_pixel();
+        TakePixels {
+            stream: self,
+            remaining,
+        }
+    }
+
+    /// Appends black pixels until the stream is at least the given number of pixels long.
+    fn pad(self, pixels: usize) -> Pad<Self> {
+        let remaining = pixels * self.bytes_per_pixel();
+        Pad {
+            stream: self,
+            finished: false,
+            remaining,
+        }
+    }
+
+    /// Repeats the stream the given number of times.
+    fn repeat(self, times: usize) -> Repeat<Self>
+    where
+        Self: Clone,
+    {
+        Repeat {
+            original: self,
+            current: None,
+            remaining: times,
+        }
+    }
+
+    /// Appends another stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pixels of both streams have a different number of bytes.
+    fn chain<S: PixelStreamRef>(self, other: S) -> Chain<Self, S> {
+        assert_eq!(
+            self.bytes_per_pixel(),
+            other.bytes_per_pixel(),
+            "Chained streams have different pixel sizes"
+        );
+        Chain {
+            first: self,
+            first_finished: false,
+            second: other,
+        }
+    }
+}
+
+impl<S: PixelStreamRef> PixelStreamExt for S {}
+
+/// A stream with leading black pixels, created by [`PixelStreamExt::offset()`].
+#[derive(Clone)]
+pub struct Offset<S> {
+    stream: S,
+    remaining: usize,
+}
+
+impl<S: PixelStreamRef> PixelStreamRef for Offset<S> {
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            Some(0)
+        } else {
+            self.stream.next()
+        }
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.stream.bytes_per_pixel()
+    }
+}
+
+/// A stream without its leading pixels, created by [`PixelStreamExt::skip_pixels()`].
+#[derive(Clone)]
+pub struct SkipPixels<S> {
+    stream: S,
+    remaining: usize,
+}
+
+impl<S: PixelStreamRef> PixelStreamRef for SkipPixels<S> {
+    fn next(&mut self) -> Option<u8> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            self.stream.next()?;
+        }
+        self.stream.next()
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.stream.bytes_per_pixel()
+    }
+}
+
+/// A stream with a limited length, created by [`PixelStreamExt::take_pixels()`].
+#[derive(Clone)]
+pub struct TakePixels<S> {
+    stream: S,
+    remaining: usize,
+}
+
+impl<S: PixelStreamRef> PixelStreamRef for TakePixels<S> {
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.stream.next()
+        } else {
+            None
+        }
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.stream.bytes_per_pixel()
+    }
+}
+
+/// A stream with trailing black pixels, created by [`PixelStreamExt::pad()`].
+#[derive(Clone)]
+pub struct Pad<S> {
+    stream: S,
+    finished: bool,
+    remaining: usize,
+}
+
+impl<S: PixelStreamRef> PixelStreamRef for Pad<S> {
+    fn next(&mut self) -> Option<u8> {
+        let byte = if self.finished {
+            None
+        } else {
+            self.stream.next()
+        };
+
+        let byte = match byte {
+            Some(byte) => Some(byte),
+            None => {
+                self.finished = true;
+                (self.remaining > 0).then_some(0)
+            }
+        };
+
+        self.remaining = self.remaining.saturating_sub(1);
+        byte
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.stream.bytes_per_pixel()
+    }
+}
+
+/// A repeated stream, created by [`PixelStreamExt::repeat()`].
+#[derive(Clone)]
+pub struct Repeat<S> {
+    original: S,
+    current: Option<S>,
+    remaining: usize,
+}
+
+impl<S: PixelStreamRef + Clone> PixelStreamRef for Repeat<S> {
+    fn next(&mut self) -> Option<u8> {
+        if let Some(byte) = self.current.as_mut().and_then(|stream| stream.next()) {
+            return Some(byte);
+        }
+
+        if self.remaining == 0 {
+            self.current = None;
+            return None;
+        }
+        self.remaining -= 1;
+
+        let current = self.current.insert(self.original.clone());
+        current.next()
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.original.bytes_per_pixel()
+    }
+}
+
+/// Two concatenated streams, created by [`PixelStreamExt::chain()`].
+#[derive(Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    first_finished: bool,
+    second: B,
+}
+
+impl<A: PixelStreamRef, B: PixelStreamRef> PixelStreamRef for Chain<A, B> {
+    fn next(&mut self) -> Option<u8> {
+        if !self.first_finished {
+            if let Some(byte) = self.first.next() {
+                return Some(byte);
+            }
+            self.first_finished = true;
+        }
+        self.second.next()
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.first.bytes_per_pixel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::pixelstream::IntoPixelStream;
+
+    fn collect(mut stream: impl PixelStreamRef) -> Vec<u8> {
+        core::iter::from_fn(|| stream.next()).collect()
+    }
+
+    // `[u8; 3]` pixels are RGB, they get transmitted as GRB
+    const PIXELS: [[u8; 3]; 2] = [[2, 1, 3], [5, 4, 6]];
+
+    #[test]
+    fn combinators_work_in_pixels() {
+        let stream = || PIXELS.into_pixel_stream();
+
+        assert_eq!(collect(stream().offset(1)), [0, 0, 0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(collect(stream().skip_pixels(1)), [4, 5, 6]);
+        assert_eq!(collect(stream().take_pixels(1)), [1, 2, 3]);
+        assert_eq!(collect(stream().pad(3)), [1, 2, 3, 4, 5, 6, 0, 0, 0]);
+        assert_eq!(collect(stream().pad(1)), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            collect(stream().chain(stream())),
+            [1, 2, 3, 4, 5, 6, 1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn repeat_after_other_combinators() {
+        let stream = PIXELS.into_pixel_stream().skip_pixels(1).offset(1);
+
+        assert_eq!(
+            collect(stream.repeat(2)),
+            [0, 0, 0, 4, 5, 6, 0, 0, 0, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    #[should_panic = "Chained streams have different pixel sizes"]
+    fn chain_rejects_different_pixel_sizes() {
+        let _ = PIXELS
+            .into_pixel_stream()
+            .chain([[1u8, 2, 3, 4]].into_pixel_stream());
+    }
+}
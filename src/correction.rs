@@ -0,0 +1,163 @@
+use crate::pixelstream::PixelStreamRef;
+
+/// Per-LED gain corrections, to compensate brightness differences between individual LEDs.
+///
+/// Stores one gain per channel and LED, where `255` represents `1.0` and lower
+/// values attenuate the channel. The channels are in the order they get sent
+/// to the strip; for example GRB for WS2812 strips.
+///
+/// The corrections get applied to a pixel stream via [`apply()`](CorrectionTable::apply),
+/// so the renderer doesn't need to know about them:
+///
+/// ```rust
+/// buffer.prepare_pixels([&mut corrections.apply(framebuffer.iter().into_pixel_stream())]);
+/// ```
+///
+/// # Generics:
+///
+/// * `N` - the number of LEDs. LEDs beyond that stay uncorrected.
+/// * `C` - the number of channels per LED
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrectionTable<const N: usize, const C: usize = 3> {
+    gains: [[u8; C]; N],
+}
+
+impl<const N: usize, const C: usize> CorrectionTable<N, C> {
+    /// Creates a correction table that doesn't modify any LED.
+    pub const fn new() -> Self {
+        Self {
+            gains: [[u8::MAX; C]; N],
+        }
+    }
+
+    /// Creates a correction table from raw gains.
+    pub const fn from_gains(gains: [[u8; C]; N]) -> Self {
+        Self { gains }
+    }
+
+    /// Creates a correction table from the measured brightness of each LED.
+    ///
+    /// Every channel gets attenuated to the brightness of the dimmest LED
+    /// of that channel, so all LEDs look the same. LEDs with a measurement of
+    /// zero are considered broken and are ignored.
+    ///
+    /// The measurements can be in an arbitrary unit, but have to be linear.
+    pub fn from_measurements(measurements: &[[u16; C]; N]) -> Self {
+        let mut gains = [[u8::MAX; C]; N];
+
+        for channel in 0..C {
+            let Some(target) = measurements
+                .iter()
+                .map(|measurement| measurement[channel])
+                .filter(|&value| value != 0)
+                .min()
+            else {
+                continue;
+            };
+
+            for (gain, measurement) in gains.iter_mut().zip(measurements) {
+                let measured = u32::from(measurement[channel]);
+                if let Some(scaled) = (u32::from(target) * 256).checked_div(measured) {
+                    gain[channel] = scaled.saturating_sub(1).min(255) as u8;
+                }
+            }
+        }
+
+        Self { gains }
+    }
+
+    /// The gains of a single LED.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `led` is not smaller than `N`.
+    pub fn gain(&self, led: usize) -> [u8; C] {
+        self.gains[led]
+    }
+
+    /// Sets the gains of a single LED, for example after it was replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `led` is not smaller than `N`.
+    pub fn set_gain(&mut self, led: usize, gain: [u8; C]) {
+        self.gains[led] = gain;
+    }
+
+    /// Applies the corrections to a pixel stream.
+    ///
+    /// The LEDs are delimited by the pixel size of the stream; if the stream
+    /// has more than `C` channels, the additional channels stay uncorrected.
+    pub fn apply<S: PixelStreamRef>(&self, stream: S) -> Corrected<'_, S, N, C> {
+        Corrected {
+            stream,
+            table: self,
+            led: 0,
+            channel: 0,
+        }
+    }
+}
+
+impl<const N: usize, const C: usize> Default for CorrectionTable<N, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A pixel stream with per-LED corrections, created by [`CorrectionTable::apply()`].
+pub struct Corrected<'a, S, const N: usize, const C: usize> {
+    stream: S,
+    table: &'a CorrectionTable<N, C>,
+    led: usize,
+    channel: usize,
+}
+
+impl<S: PixelStreamRef, const N: usize, const C: usize> PixelStreamRef for Corrected<'_, S, N, C> {
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.stream.next()?;
+
+        let gain = self
+            .table
+            .gains
+            .get(self.led)
+            .and_then(|gains| gains.get(self.channel))
+            .copied()
+            .unwrap_or(u8::MAX);
+
+        self.channel += 1;
+        if self.channel >= self.stream.bytes_per_pixel() {
+            self.channel = 0;
+            self.led += 1;
+        }
+
+        Some(((u16::from(byte) * (u16::from(gain) + 1)) >> 8) as u8)
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.stream.bytes_per_pixel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::pixelstream::IntoPixelStream;
+
+    fn collect(mut stream: impl PixelStreamRef) -> Vec<u8> {
+        core::iter::from_fn(|| stream.next()).collect()
+    }
+
+    #[test]
+    fn gains_follow_the_leds_of_the_stream() {
+        let table = CorrectionTable::<2>::from_gains([[127, 255, 255], [255, 63, 255]]);
+
+        let corrected = collect(table.apply([[200u8; 4]; 3].into_pixel_stream()));
+
+        assert_eq!(
+            corrected,
+            [100, 200, 200, 200, 200, 50, 200, 200, 200, 200, 200, 200]
+        );
+    }
+}
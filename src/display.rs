@@ -0,0 +1,281 @@
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{Rgb888, RgbColor},
+    primitives::Rectangle,
+};
+use imxrt_ral::{flexio, Valid};
+
+use crate::{
+    flexio::{PrepareSummary, PreprocessedPixels, WS2812Driver},
+    matrix::Matrix,
+    pins::Pins,
+};
+
+/// An `embedded-graphics` display backed by an LED matrix.
+///
+/// Holds a framebuffer in the size of the [`Matrix`]; drawing operations
+/// only modify the framebuffer and are clipped to the matrix. The
+/// framebuffer gets sent to the LED strips via [`flush()`](MatrixDisplay::flush).
+///
+/// # Generics:
+///
+/// * `S` - the number of pixels the framebuffer can hold.
+///   Has to be at least the number of pixels of the matrix.
+///
+/// ```rust
+/// const MATRIX: Matrix = Matrix::new(30, 10).serpentine(true).tiles(1, 3);
+///
+/// let mut display = MatrixDisplay::<{ 30 * 30 }>::new(MATRIX);
+///
+/// Text::new("Hi!", Point::new(2, 12), MonoTextStyle::new(&FONT_6X10, Rgb888::RED))
+///     .draw(&mut display)
+///     .unwrap();
+///
+/// display
+///     .flush(&mut neopixel, &mut buffer, &mut dma, DMA_MUX_SRC)
+///     .await
+///     .unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-graphics")))]
+#[derive(Debug, Clone)]
+pub struct MatrixDisplay<const S: usize> {
+    matrix: Matrix,
+    framebuffer: [Rgb888; S],
+}
+
+impl<const S: usize> MatrixDisplay<S> {
+    /// Creates a new display with a black framebuffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix has more than `S` pixels.
+    pub const fn new(matrix: Matrix) -> Self {
+        assert!(
+            matrix.width() * matrix.height() <= S,
+            "Framebuffer too small for matrix"
+        );
+
+        Self {
+            matrix,
+            framebuffer: [Rgb888::BLACK; S],
+        }
+    }
+
+    /// The layout of the display.
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    /// The framebuffer of the display, row by row.
+    pub fn framebuffer(&self) -> &[Rgb888] {
+        &self.framebuffer[..self.len()]
+    }
+
+    /// The mutable framebuffer of the display, row by row.
+    pub fn framebuffer_mut(&mut self) -> &mut [Rgb888] {
+        let len = self.len();
+        &mut self.framebuffer[..len]
+    }
+
+    /// The number of pixels of the display.
+    fn len(&self) -> usize {
+        self.matrix.width() * self.matrix.height()
+    }
+
+    /// Renders the framebuffer into a preprocessed pixel buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix spans more LED strips than the buffer has lanes.
+    pub fn render<const N: usize, const L: usize, const P: usize>(
+        &self,
+        buffer: &mut PreprocessedPixels<N, L, P>,
+    ) -> PrepareSummary<L> {
+        self.matrix.render(self.framebuffer(), buffer)
+    }
+
+    /// Renders the framebuffer and sends it to the LED strips.
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - The driver of the LED strips
+    /// * `buffer` - The buffer the framebuffer gets rendered into
+    /// * `dma` - The dma channel that should be used to transmit the data
+    /// * `dma_signal_id` - The signal the FlexIO unit uses to communicate with the DMA.
+    ///   See [`WS2812Driver::write_dma()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix spans more LED strips than the driver has lanes.
+    pub async fn flush<const N: u8, const L: usize, PINS, const N2: usize, const P: usize>(
+        &self,
+        driver: &mut WS2812Driver<N, L, PINS>,
+        buffer: &mut PreprocessedPixels<N2, L, P>,
+        dma: &mut imxrt_dma::channel::Channel,
+        dma_signal_id: u32,
+    ) -> Result<PrepareSummary<L>, imxrt_dma::Error>
+    where
+        PINS: Pins<N, L>,
+        flexio::Instance<N>: Valid,
+    {
+        let summary = self.render(buffer);
+        driver
+            .write_dma(buffer, dma, dma_signal_id, async {})
+            .await?;
+        Ok(summary)
+    }
+
+    /// Same as [`flush()`](MatrixDisplay::flush), but blocks until completion.
+    ///
+    /// Do not use this function in an async context as it will busy-wait
+    /// internally.
+    pub fn flush_blocking<const N: u8, const L: usize, PINS, const N2: usize, const P: usize>(
+        &self,
+        driver: &mut WS2812Driver<N, L, PINS>,
+        buffer: &mut PreprocessedPixels<N2, L, P>,
+        dma: &mut imxrt_dma::channel::Channel,
+        dma_signal_id: u32,
+    ) -> Result<PrepareSummary<L>, imxrt_dma::Error>
+    where
+        PINS: Pins<N, L>,
+        flexio::Instance<N>: Valid,
+    {
+        let summary = self.render(buffer);
+        driver.write_dma_blocking(buffer, dma, dma_signal_id, || ())?;
+        Ok(summary)
+    }
+}
+
+impl<const S: usize> OriginDimensions for MatrixDisplay<S> {
+    fn size(&self) -> Size {
+        Size::new(self.matrix.width() as u32, self.matrix.height() as u32)
+    }
+}
+
+impl<const S: usize> DrawTarget for MatrixDisplay<S> {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics_core::Pixel<Self::Color>>,
+    {
+        let width = self.matrix.width();
+        let height = self.matrix.height();
+
+        for embedded_graphics_core::Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                continue;
+            };
+            if x < width && y < height {
+                self.framebuffer[y * width + x] = color;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let width = self.matrix.width();
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * width;
+            self.framebuffer[row + area.top_left.x as usize..=row + bottom_right.x as usize]
+                .fill(color);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.framebuffer_mut().fill(color);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use embedded_graphics_core::{geometry::Point, Pixel};
+
+    use super::*;
+
+    // 4x3 pixels, with room to spare in the framebuffer
+    const MATRIX: Matrix = Matrix::new(4, 3);
+
+    fn lit(display: &MatrixDisplay<16>) -> Vec<usize> {
+        (0..16)
+            .filter(|&i| display.framebuffer[i] != Rgb888::BLACK)
+            .collect()
+    }
+
+    #[test]
+    fn draw_iter_indexes_row_by_row() {
+        let mut display = MatrixDisplay::<16>::new(MATRIX);
+        display
+            .draw_iter([
+                Pixel(Point::new(0, 0), Rgb888::RED),
+                Pixel(Point::new(3, 0), Rgb888::GREEN),
+                Pixel(Point::new(1, 2), Rgb888::BLUE),
+            ])
+            .unwrap();
+
+        assert_eq!(display.framebuffer[0], Rgb888::RED);
+        assert_eq!(display.framebuffer[3], Rgb888::GREEN);
+        assert_eq!(display.framebuffer[9], Rgb888::BLUE);
+        assert_eq!(lit(&display), [0, 3, 9]);
+    }
+
+    #[test]
+    fn draw_iter_clips() {
+        let mut display = MatrixDisplay::<16>::new(MATRIX);
+        display
+            .draw_iter([
+                Pixel(Point::new(-1, 0), Rgb888::RED),
+                Pixel(Point::new(0, -1), Rgb888::RED),
+                Pixel(Point::new(4, 0), Rgb888::RED),
+                Pixel(Point::new(0, 3), Rgb888::RED),
+                Pixel(Point::new(i32::MAX, i32::MAX), Rgb888::RED),
+            ])
+            .unwrap();
+
+        assert_eq!(lit(&display), []);
+    }
+
+    #[test]
+    fn fill_solid_clips() {
+        let mut display = MatrixDisplay::<16>::new(MATRIX);
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(2, -1), Size::new(10, 3)),
+                Rgb888::WHITE,
+            )
+            .unwrap();
+        assert_eq!(lit(&display), [2, 3, 6, 7]);
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(4, 0), Size::new(2, 2)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        assert_eq!(lit(&display), [2, 3, 6, 7]);
+    }
+
+    #[test]
+    fn clear_keeps_spare_framebuffer() {
+        let mut display = MatrixDisplay::<16>::new(MATRIX);
+        display.clear(Rgb888::WHITE).unwrap();
+
+        assert_eq!(display.framebuffer().len(), 12);
+        assert_eq!(lit(&display), (0..12).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,199 @@
+use palette::LinSrgb;
+
+/// A high precision color that can be quantized by [`Dither`].
+pub trait DitherInput {
+    /// Converts the color to 16-bit linear RGB.
+    fn into_rgb16(self) -> [u16; 3];
+}
+
+impl DitherInput for LinSrgb<u16> {
+    fn into_rgb16(self) -> [u16; 3] {
+        [self.red, self.green, self.blue]
+    }
+}
+
+impl DitherInput for LinSrgb<f32> {
+    fn into_rgb16(self) -> [u16; 3] {
+        // Float to int casts saturate, so no clamping is required
+        [self.red, self.green, self.blue].map(|c| (c * 65535.0 + 0.5) as u16)
+    }
+}
+
+impl DitherInput for [u16; 3] {
+    fn into_rgb16(self) -> [u16; 3] {
+        self
+    }
+}
+
+impl<T> DitherInput for &T
+where
+    T: DitherInput + Copy,
+{
+    fn into_rgb16(self) -> [u16; 3] {
+        (*self).into_rgb16()
+    }
+}
+
+/// Temporal dithering of high precision colors to 8-bit output.
+///
+/// The quantization error of every pixel gets carried over to the next frame,
+/// so that on average over multiple frames, the LEDs show the exact
+/// high precision color. This makes slow fades at low brightness look continuous
+/// instead of visibly stepping through the 8-bit values.
+///
+/// Works best at high framerates; the state has to be kept across frames.
+///
+/// # Generics:
+///
+/// * `N` - the number of pixels the error state is kept for.
+///   Pixels beyond that are rounded without dithering.
+///
+/// # Example
+///
+/// ```rust
+/// let mut dither = Dither::<NUM_PIXELS>::new();
+///
+/// loop {
+///     render_frame(&mut framebuffer); // `LinSrgb<u16>` pixels
+///     buffer.prepare_pixels([&mut dither.apply(&framebuffer).into_pixel_stream()]);
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dither<const N: usize> {
+    errors: [[u8; 3]; N],
+}
+
+impl<const N: usize> Dither<N> {
+    /// Creates a new dithering state.
+    pub const fn new() -> Self {
+        Self {
+            errors: [[0; 3]; N],
+        }
+    }
+
+    /// Clears the carried error of all pixels.
+    pub fn reset(&mut self) {
+        self.errors = [[0; 3]; N];
+    }
+
+    /// Quantizes one frame of high precision pixels to 8-bit.
+    ///
+    /// The returned iterator updates the error state while it gets consumed.
+    pub fn apply<I>(&mut self, pixels: I) -> Dithered<'_, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: DitherInput,
+    {
+        Dithered {
+            pixels: pixels.into_iter(),
+            errors: self.errors.iter_mut(),
+        }
+    }
+}
+
+impl<const N: usize> Default for Dither<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The iterator returned by [`Dither::apply()`].
+pub struct Dithered<'a, I> {
+    pixels: I,
+    errors: core::slice::IterMut<'a, [u8; 3]>,
+}
+
+impl<I> Iterator for Dithered<'_, I>
+where
+    I: Iterator,
+    I::Item: DitherInput,
+{
+    type Item = LinSrgb<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let color = self.pixels.next()?.into_rgb16();
+
+        let [red, green, blue] = match self.errors.next() {
+            Some(errors) => {
+                let mut result = [0; 3];
+                for ((out, value), error) in result.iter_mut().zip(color).zip(errors) {
+                    let value = u32::from(value) + u32::from(*error);
+                    let quantized = (value >> 8).min(255);
+                    *error = (value - (quantized << 8)).min(255) as u8;
+                    *out = quantized as u8;
+                }
+                result
+            }
+            None => color.map(|value| ((u32::from(value) + 0x80) >> 8).min(255) as u8),
+        };
+
+        Some(LinSrgb::new(red, green, blue))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pixels.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn average_converges_to_input() {
+        let mut dither = Dither::<3>::new();
+        let input = [[1u16, 128, 300], [4095, 32768, 40000], [65000, 255, 257]];
+
+        let mut sums = [[0u32; 3]; 3];
+        for _ in 0..256 {
+            for (sum, pixel) in sums.iter_mut().zip(dither.apply(input)) {
+                for (sum, channel) in sum.iter_mut().zip([pixel.red, pixel.green, pixel.blue]) {
+                    *sum += u32::from(channel);
+                }
+            }
+        }
+
+        // The sum of 256 frames of 8-bit output has the precision of the 16-bit input
+        for (sum, input) in sums.iter().flatten().zip(input.iter().flatten()) {
+            assert!(u32::from(*input) - sum <= 1, "{sum} != {input}");
+        }
+    }
+
+    #[test]
+    fn black_stays_black() {
+        let mut dither = Dither::<1>::new();
+
+        for _ in 0..1000 {
+            let pixel = dither.apply([[0u16; 3]]).next().unwrap();
+            assert_eq!(pixel, LinSrgb::new(0, 0, 0));
+        }
+        assert_eq!(dither.errors, [[0; 3]]);
+    }
+
+    #[test]
+    fn full_scale_stays_bounded() {
+        let mut dither = Dither::<1>::new();
+
+        for _ in 0..1000 {
+            let pixel = dither.apply([[u16::MAX; 3]]).next().unwrap();
+            assert_eq!(pixel, LinSrgb::new(255, 255, 255));
+        }
+        assert_eq!(dither.errors, [[255; 3]]);
+
+        // The carried error doesn't leak into darker frames
+        let pixel = dither.apply([[0u16; 3]]).next().unwrap();
+        assert_eq!(pixel, LinSrgb::new(0, 0, 0));
+    }
+
+    #[test]
+    fn pixels_without_state_get_rounded() {
+        let mut dither = Dither::<0>::new();
+
+        let pixels: [_; 2] = {
+            let mut pixels = dither.apply([[0x017F_u16, 0x0180, u16::MAX]; 2]);
+            core::array::from_fn(|_| pixels.next().unwrap())
+        };
+        assert_eq!(pixels, [LinSrgb::new(1, 2, 255); 2]);
+    }
+}
@@ -0,0 +1,65 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{blend, phase, smooth, triangle, Effect, BLACK};
+
+/// All pixels slowly fade in and out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breathe {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    period: Duration,
+}
+
+impl Breathe {
+    /// Creates a breathing effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color at the brightest point
+    /// * `period` - The duration of one breath
+    pub const fn new(color: LinSrgb<u8>, period: Duration) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            period,
+        }
+    }
+
+    /// Sets the color at the darkest point. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+}
+
+impl Effect for Breathe {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        let amount = smooth(triangle(phase(time, self.period)));
+        pixels.fill(blend(self.background, self.color, amount));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_in_and_out() {
+        let color = LinSrgb::new(200, 100, 0);
+        let mut breathe = Breathe::new(color, Duration::from_secs(2));
+        let mut pixels = [BLACK; 3];
+
+        breathe.render(Duration::ZERO, &mut pixels);
+        assert_eq!(pixels, [BLACK; 3]);
+
+        breathe.render(Duration::from_millis(500), &mut pixels);
+        assert_eq!(pixels, [LinSrgb::new(100, 50, 0); 3]);
+
+        breathe.render(Duration::from_secs(1), &mut pixels);
+        assert_eq!(pixels, [color; 3]);
+
+        breathe.render(Duration::from_secs(2), &mut pixels);
+        assert_eq!(pixels, [BLACK; 3]);
+    }
+}
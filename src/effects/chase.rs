@@ -0,0 +1,174 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{distance, Effect, BLACK};
+
+/// Groups of lit pixels that run along the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chase {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    speed: u32,
+    length: usize,
+    spacing: usize,
+}
+
+impl Chase {
+    /// Creates a chase of single pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color of the lit pixels
+    /// * `speed` - The speed of the pixels, in pixels per second
+    pub const fn new(color: LinSrgb<u8>, speed: u32) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            speed,
+            length: 1,
+            spacing: 25,
+        }
+    }
+
+    /// Sets the color of the unlit pixels. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Sets the number of lit pixels per group. Defaults to `1`.
+    pub const fn length(self, length: usize) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Sets the distance between the starts of two groups, in pixels. Defaults to `25`.
+    pub const fn spacing(self, spacing: usize) -> Self {
+        Self { spacing, ..self }
+    }
+}
+
+impl Effect for Chase {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        render_groups(
+            pixels,
+            distance(time, self.speed),
+            self.spacing,
+            self.length,
+            self.color,
+            self.background,
+        );
+    }
+}
+
+/// The classic theater marquee effect: every third pixel is lit,
+/// and the lit pixels step forward one pixel at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TheaterChase {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    step: Duration,
+    spacing: usize,
+}
+
+impl TheaterChase {
+    /// Creates a theater chase.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color of the lit pixels
+    /// * `step` - The time between two steps
+    pub const fn new(color: LinSrgb<u8>, step: Duration) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            step,
+            spacing: 3,
+        }
+    }
+
+    /// Sets the color of the unlit pixels. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Sets the distance between two lit pixels. Defaults to `3`.
+    pub const fn spacing(self, spacing: usize) -> Self {
+        Self { spacing, ..self }
+    }
+}
+
+impl Effect for TheaterChase {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        let steps = (time.as_micros() as u64)
+            .checked_div(self.step.as_micros() as u64)
+            .unwrap_or(0);
+
+        render_groups(pixels, steps, self.spacing, 1, self.color, self.background);
+    }
+}
+
+/// Renders evenly spaced groups of lit pixels, shifted by the given offset.
+fn render_groups(
+    pixels: &mut [LinSrgb<u8>],
+    offset: u64,
+    spacing: usize,
+    length: usize,
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+) {
+    if spacing == 0 {
+        pixels.fill(background);
+        return;
+    }
+
+    let offset = (offset % spacing as u64) as usize;
+    for (position, pixel) in pixels.iter_mut().enumerate() {
+        let group_position = (position + spacing - offset) % spacing;
+        *pixel = if group_position < length {
+            color
+        } else {
+            background
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const C: LinSrgb<u8> = LinSrgb::new(255, 255, 255);
+    const B: LinSrgb<u8> = BLACK;
+
+    #[test]
+    fn chase_moves_groups() {
+        let mut chase = Chase::new(C, 2).length(2).spacing(4);
+        let mut pixels = [BLACK; 6];
+
+        chase.render(Duration::ZERO, &mut pixels);
+        assert_eq!(pixels, [C, C, B, B, C, C]);
+
+        chase.render(Duration::from_millis(1500), &mut pixels);
+        assert_eq!(pixels, [C, B, B, C, C, B]);
+    }
+
+    #[test]
+    fn theater_chase_steps() {
+        let mut chase = TheaterChase::new(C, Duration::from_millis(100));
+        let mut pixels = [BLACK; 6];
+
+        chase.render(Duration::from_millis(50), &mut pixels);
+        assert_eq!(pixels, [C, B, B, C, B, B]);
+
+        chase.render(Duration::from_millis(250), &mut pixels);
+        assert_eq!(pixels, [B, B, C, B, B, C]);
+    }
+
+    #[test]
+    fn zero_spacing() {
+        let mut chase = Chase::new(C, 2).spacing(0);
+        let mut pixels = [C; 3];
+
+        chase.render(Duration::from_secs(1), &mut pixels);
+        assert_eq!(pixels, [B; 3]);
+    }
+}
@@ -0,0 +1,95 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{blend, distance, Effect, BLACK};
+
+/// A bright head with a fading tail that runs along the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comet {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    speed: u32,
+    tail: usize,
+}
+
+impl Comet {
+    /// Creates a comet.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color of the head of the comet
+    /// * `speed` - The speed of the comet, in pixels per second
+    pub const fn new(color: LinSrgb<u8>, speed: u32) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            speed,
+            tail: 10,
+        }
+    }
+
+    /// Sets the color of the pixels that aren't part of the comet. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Sets the length of the tail, in pixels. Defaults to `10`.
+    pub const fn tail(self, tail: usize) -> Self {
+        Self { tail, ..self }
+    }
+}
+
+impl Effect for Comet {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        // The comet starts in front of the strip and leaves it
+        // completely before it starts again.
+        let track = (pixels.len() + self.tail) as u64;
+        if track == 0 {
+            return;
+        }
+        let head = (distance(time, self.speed) % track) as usize;
+
+        for (position, pixel) in pixels.iter_mut().enumerate() {
+            let Some(behind) = head.checked_sub(position) else {
+                *pixel = self.background;
+                continue;
+            };
+
+            *pixel = if behind == 0 {
+                self.color
+            } else if behind <= self.tail {
+                let amount = 255 - behind * 255 / (self.tail + 1);
+                blend(self.background, self.color, amount as u8)
+            } else {
+                self.background
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: u8) -> LinSrgb<u8> {
+        LinSrgb::new(value, value, value)
+    }
+
+    #[test]
+    fn tail_fades() {
+        let mut comet = Comet::new(gray(255), 10).tail(2);
+        let mut pixels = [BLACK; 5];
+
+        comet.render(Duration::from_millis(400), &mut pixels);
+        assert_eq!(pixels, [gray(0), gray(0), gray(85), gray(170), gray(255)]);
+
+        // The head has left the strip, only the end of the tail is visible
+        comet.render(Duration::from_millis(600), &mut pixels);
+        assert_eq!(pixels, [gray(0), gray(0), gray(0), gray(0), gray(85)]);
+
+        // One pass takes the length of the strip plus the tail
+        comet.render(Duration::from_millis(700), &mut pixels);
+        assert_eq!(pixels, [gray(255), gray(0), gray(0), gray(0), gray(0)]);
+    }
+}
@@ -0,0 +1,106 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{blend, phase, smooth, triangle, Effect};
+
+/// A linear transition between two colors along the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gradient {
+    from: LinSrgb<u8>,
+    to: LinSrgb<u8>,
+    period: Option<Duration>,
+}
+
+impl Gradient {
+    /// Creates a static gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The color of the first pixel
+    /// * `to` - The color of the last pixel
+    pub const fn new(from: LinSrgb<u8>, to: LinSrgb<u8>) -> Self {
+        Self {
+            from,
+            to,
+            period: None,
+        }
+    }
+
+    /// Makes the gradient scroll along the strip.
+    ///
+    /// A scrolling gradient transitions from the first color to the second
+    /// and back, so it can wrap around seamlessly.
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The time it takes the gradient to move by the length of the strip
+    pub const fn scrolling(self, period: Duration) -> Self {
+        Self {
+            period: Some(period),
+            ..self
+        }
+    }
+}
+
+impl Effect for Gradient {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        let len = pixels.len();
+
+        match self.period {
+            None => {
+                let last = len.saturating_sub(1).max(1);
+                for (position, pixel) in pixels.iter_mut().enumerate() {
+                    let amount = position * 255 / last;
+                    *pixel = blend(self.from, self.to, amount as u8);
+                }
+            }
+            Some(period) => {
+                let offset = usize::from(phase(time, period));
+                for (position, pixel) in pixels.iter_mut().enumerate() {
+                    let position = (position * 65536 / len + 65536 - offset) as u16;
+                    *pixel = blend(self.from, self.to, smooth(triangle(position)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: u8) -> LinSrgb<u8> {
+        LinSrgb::new(value, value, value)
+    }
+
+    #[test]
+    fn static_gradient() {
+        let mut gradient = Gradient::new(LinSrgb::new(0, 0, 0), LinSrgb::new(255, 0, 100));
+        let mut pixels = [gray(0); 5];
+
+        gradient.render(Duration::from_secs(3), &mut pixels);
+        assert_eq!(
+            pixels,
+            [
+                LinSrgb::new(0, 0, 0),
+                LinSrgb::new(63, 0, 24),
+                LinSrgb::new(127, 0, 49),
+                LinSrgb::new(191, 0, 74),
+                LinSrgb::new(255, 0, 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrolling_gradient() {
+        let mut gradient = Gradient::new(gray(0), gray(255)).scrolling(Duration::from_secs(1));
+        let mut pixels = [gray(0); 4];
+
+        gradient.render(Duration::ZERO, &mut pixels);
+        assert_eq!(pixels, [gray(0), gray(128), gray(255), gray(126)]);
+
+        gradient.render(Duration::from_millis(250), &mut pixels);
+        assert_eq!(pixels, [gray(126), gray(0), gray(128), gray(255)]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Parameterised, time-based LED effects.
+//!
+//! All effects implement [`Effect`] and render into slices of
+//! [`LinSrgb<u8>`], using integer math only. The effects are based
+//! on the current time rather than on a frame counter, so their speed
+//! doesn't depend on the framerate.
+//!
+//! ```rust
+//! let mut effect = Comet::new(LinSrgb::new(255, 80, 0), 60).tail(15);
+//! let mut framebuffer = [LinSrgb::new(0, 0, 0); NUM_PIXELS];
+//!
+//! loop {
+//!     effect.render(time_since_start(), &mut framebuffer);
+//!     neopixel.write([&mut framebuffer.iter().into_pixel_stream()]);
+//! }
+//! ```
+
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+mod breathe;
+mod chase;
+mod comet;
+mod gradient;
+mod rainbow;
+mod timeline;
+mod twinkle;
+mod wipe;
+
+pub use breathe::Breathe;
+pub use chase::{Chase, TheaterChase};
+pub use comet::Comet;
+pub use gradient::Gradient;
+pub use rainbow::Rainbow;
+pub use timeline::{Cue, Easing, Timeline, Transition};
+pub use twinkle::Twinkle;
+pub use wipe::ColorWipe;
+
+/// An animated LED effect.
+///
+/// Implemented for all closures with a matching signature,
+/// so custom effects don't require a dedicated type.
+pub trait Effect {
+    /// Renders the effect at the given point in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time since the start of the effect
+    /// * `pixels` - The pixels to render into
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]);
+}
+
+impl<F> Effect for F
+where
+    F: FnMut(Duration, &mut [LinSrgb<u8>]),
+{
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        self(time, pixels)
+    }
+}
+
+/// The black color, the default background of the effects.
+const BLACK: LinSrgb<u8> = LinSrgb::new(0, 0, 0);
+
+/// The position inside of a periodic cycle, from `0` to `65535`.
+fn phase(time: Duration, period: Duration) -> u16 {
+    let period = period.as_micros() as u64;
+    if period == 0 {
+        return 0;
+    }
+
+    let time = time.as_micros() as u64;
+    ((time % period) * 65536 / period) as u16
+}
+
+/// The distance that something moving at the given speed covers, in pixels.
+fn distance(time: Duration, pixels_per_second: u32) -> u64 {
+    (time.as_micros() as u64).saturating_mul(u64::from(pixels_per_second)) / 1_000_000
+}
+
+/// A triangle wave that rises from `0` to `255` and falls back to `0` within one cycle.
+fn triangle(phase: u16) -> u8 {
+    if phase < 0x8000 {
+        (phase >> 7) as u8
+    } else {
+        ((u16::MAX - phase) >> 7) as u8
+    }
+}
+
+/// Smooths the ends of a transition, approximating a sine wave when applied to [`triangle`].
+fn smooth(x: u8) -> u8 {
+    let x = u32::from(x);
+    (x * x * (3 * 255 - 2 * x) / (255 * 255)) as u8
+}
+
+/// Blends two colors; an `amount` of `0` returns `from`, an `amount` of `255` returns `to`.
+fn blend(from: LinSrgb<u8>, to: LinSrgb<u8>, amount: u8) -> LinSrgb<u8> {
+    let channel = |from: u8, to: u8| {
+        let difference = i32::from(to) - i32::from(from);
+        (i32::from(from) + difference * i32::from(amount) / 255) as u8
+    };
+
+    LinSrgb::new(
+        channel(from.red, to.red),
+        channel(from.green, to.green),
+        channel(from.blue, to.blue),
+    )
+}
+
+/// A cheap integer hash, used as a stateless random number generator.
+fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846CA68B);
+    x ^= x >> 16;
+    x
+}
@@ -0,0 +1,91 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{phase, Effect};
+use crate::pixel::hsv_to_rgb;
+
+/// A rainbow that moves along the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rainbow {
+    period: Duration,
+    wavelength: usize,
+    saturation: u8,
+    value: u8,
+}
+
+impl Rainbow {
+    /// Creates a rainbow that cycles through all hues within the given period.
+    pub const fn new(period: Duration) -> Self {
+        Self {
+            period,
+            wavelength: 256,
+            saturation: u8::MAX,
+            value: u8::MAX,
+        }
+    }
+
+    /// Sets the number of pixels that span all hues.
+    ///
+    /// A wavelength of `0` shows the same hue on all pixels. Defaults to `256`.
+    pub const fn wavelength(self, wavelength: usize) -> Self {
+        Self { wavelength, ..self }
+    }
+
+    /// Sets the saturation of the colors. Defaults to `255`.
+    pub const fn saturation(self, saturation: u8) -> Self {
+        Self { saturation, ..self }
+    }
+
+    /// Sets the brightness of the colors. Defaults to `255`.
+    pub const fn value(self, value: u8) -> Self {
+        Self { value, ..self }
+    }
+}
+
+impl Effect for Rainbow {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        let offset = (phase(time, self.period) >> 8) as usize;
+
+        for (position, pixel) in pixels.iter_mut().enumerate() {
+            let spread = (position * 256).checked_div(self.wavelength).unwrap_or(0);
+            let hue = (offset + spread) as u8;
+            let [red, green, blue] = hsv_to_rgb(hue, self.saturation, self.value);
+            *pixel = LinSrgb::new(red, green, blue);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(rainbow: &mut Rainbow, millis: u64) -> [LinSrgb<u8>; 5] {
+        let mut pixels = [LinSrgb::new(0, 0, 0); 5];
+        rainbow.render(Duration::from_millis(millis), &mut pixels);
+        pixels
+    }
+
+    #[test]
+    fn moves_along_the_strip() {
+        let mut rainbow = Rainbow::new(Duration::from_secs(1)).wavelength(4);
+
+        let start = render(&mut rainbow, 0);
+        assert_eq!(start[0], LinSrgb::new(255, 0, 0));
+        // The hues repeat after one wavelength
+        assert_eq!(start[4], start[0]);
+
+        // A quarter period shifts the hues by a quarter of the circle, i.e. one pixel
+        let shifted = render(&mut rainbow, 250);
+        assert_eq!(shifted[..4], start[1..]);
+    }
+
+    #[test]
+    fn zero_wavelength() {
+        let mut rainbow = Rainbow::new(Duration::from_secs(1))
+            .wavelength(0)
+            .saturation(0)
+            .value(80);
+        assert_eq!(render(&mut rainbow, 300), [LinSrgb::new(80, 80, 80); 5]);
+    }
+}
@@ -0,0 +1,360 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{blend, Effect, BLACK};
+
+/// The curve with which a transition progresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and accelerates.
+    EaseIn,
+    /// Starts fast and decelerates.
+    EaseOut,
+    /// Starts slow, accelerates and decelerates again.
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Applies the easing curve to a progress, from `0` to `65535`.
+    pub const fn apply(self, progress: u16) -> u16 {
+        const ONE: u64 = u16::MAX as u64;
+        let x = progress as u64;
+
+        let eased = match self {
+            Self::Linear => x,
+            Self::EaseIn => x * x / ONE,
+            Self::EaseOut => ONE - (ONE - x) * (ONE - x) / ONE,
+            Self::EaseInOut => x * x * (3 * ONE - 2 * x) / (ONE * ONE),
+        };
+
+        eased as u16
+    }
+}
+
+/// The way one cue of a [`Timeline`] replaces the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transition {
+    /// The cue replaces the previous one immediately.
+    #[default]
+    Cut,
+    /// The cue fades in while the previous one fades out.
+    Crossfade {
+        /// The length of the transition.
+        duration: Duration,
+        /// The progression of the transition.
+        easing: Easing,
+    },
+    /// The cue gets wiped in from the start of the strip.
+    Wipe {
+        /// The length of the transition.
+        duration: Duration,
+        /// The progression of the transition.
+        easing: Easing,
+    },
+}
+
+impl Transition {
+    /// A crossfade of the given length, with the default easing.
+    pub const fn crossfade(duration: Duration) -> Self {
+        Self::Crossfade {
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    /// A wipe of the given length, with the default easing.
+    pub const fn wipe(duration: Duration) -> Self {
+        Self::Wipe {
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    /// The length of the transition.
+    pub const fn duration(&self) -> Duration {
+        match *self {
+            Self::Cut => Duration::ZERO,
+            Self::Crossfade { duration, .. } | Self::Wipe { duration, .. } => duration,
+        }
+    }
+}
+
+/// A single entry of a [`Timeline`].
+pub struct Cue<'a> {
+    effect: &'a mut dyn Effect,
+    duration: Duration,
+    transition: Transition,
+}
+
+impl<'a> Cue<'a> {
+    /// Creates a cue that plays an effect for the given duration.
+    pub fn new(effect: &'a mut dyn Effect, duration: Duration) -> Self {
+        Self {
+            effect,
+            duration,
+            transition: Transition::Cut,
+        }
+    }
+
+    /// Sets the transition from the previous cue to this one.
+    ///
+    /// The transition happens during the first part of this cue,
+    /// while the previous effect keeps running.
+    pub fn transition(self, transition: Transition) -> Self {
+        Self { transition, ..self }
+    }
+}
+
+/// Plays a sequence of effects, with transitions between them.
+///
+/// The timeline is driven by a monotonic timestamp, like the time since boot,
+/// instead of a frame counter. This way, it plays at the same speed independent
+/// of the framerate, no matter if the frames get sent via
+/// [`write()`](crate::WS2812Driver::write) or [`write_dma()`](crate::WS2812Driver::write_dma).
+///
+/// ```rust
+/// let mut rainbow = Rainbow::new(Duration::from_secs(5));
+/// let mut comet = Comet::new(LinSrgb::new(255, 80, 0), 60);
+///
+/// let mut timeline = Timeline::<2, NUM_PIXELS>::new([
+///     Cue::new(&mut rainbow, Duration::from_secs(30)),
+///     Cue::new(&mut comet, Duration::from_secs(30))
+///         .transition(Transition::crossfade(Duration::from_secs(2))),
+/// ])
+/// .looping(true);
+///
+/// loop {
+///     let frame = timeline.render(time_since_boot());
+///     neopixel.write([&mut frame.iter().into_pixel_stream()]);
+/// }
+/// ```
+///
+/// # Generics:
+///
+/// * `C` - the number of cues
+/// * `S` - the number of pixels per frame
+pub struct Timeline<'a, const C: usize, const S: usize> {
+    cues: [Cue<'a>; C],
+    looping: bool,
+    start: Option<Duration>,
+    frame: [LinSrgb<u8>; S],
+    previous_frame: [LinSrgb<u8>; S],
+}
+
+impl<'a, const C: usize, const S: usize> Timeline<'a, C, S> {
+    /// Creates a timeline that plays the given cues in order.
+    ///
+    /// After the last cue finished, it keeps playing.
+    pub fn new(cues: [Cue<'a>; C]) -> Self {
+        Self {
+            cues,
+            looping: false,
+            start: None,
+            frame: [BLACK; S],
+            previous_frame: [BLACK; S],
+        }
+    }
+
+    /// Sets whether the timeline starts over after the last cue finished.
+    ///
+    /// When looping, the transition of the first cue is used to go
+    /// from the last cue back to the first one.
+    pub fn looping(self, looping: bool) -> Self {
+        Self { looping, ..self }
+    }
+
+    /// Starts the timeline at the given timestamp.
+    ///
+    /// Without calling this, the timeline starts at the first rendered frame.
+    pub fn start(&mut self, now: Duration) {
+        self.start = Some(now);
+    }
+
+    /// The total length of all cues.
+    pub fn duration(&self) -> Duration {
+        self.cues.iter().map(|cue| cue.duration).sum()
+    }
+
+    /// Renders the frame at the given timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time. Has to be monotonic; its origin doesn't matter.
+    pub fn render(&mut self, now: Duration) -> &[LinSrgb<u8>; S] {
+        if C == 0 {
+            self.frame.fill(BLACK);
+            return &self.frame;
+        }
+
+        let start = *self.start.get_or_insert(now);
+        let total = self.duration();
+        let elapsed = now.saturating_sub(start);
+
+        let wrapped = self.looping && elapsed >= total && !total.is_zero();
+        let elapsed = if self.looping && !total.is_zero() {
+            Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64)
+        } else {
+            elapsed
+        };
+
+        // Find the current cue; after the end, the last cue keeps playing
+        let mut current = C - 1;
+        let mut cue_start = total - self.cues[C - 1].duration;
+        let mut offset = Duration::ZERO;
+        for (index, cue) in self.cues.iter().enumerate() {
+            if elapsed < offset + cue.duration {
+                current = index;
+                cue_start = offset;
+                break;
+            }
+            offset += cue.duration;
+        }
+        let time = elapsed - cue_start;
+
+        let previous = if current > 0 {
+            Some(current - 1)
+        } else if wrapped && C > 1 {
+            Some(C - 1)
+        } else {
+            None
+        };
+
+        let transition = self.cues[current].transition;
+        let (Some(previous), true) = (previous, time < transition.duration()) else {
+            self.cues[current].effect.render(time, &mut self.frame);
+            return &self.frame;
+        };
+
+        let (previous_cue, current_cue) = if previous < current {
+            let (head, tail) = self.cues.split_at_mut(current);
+            (&mut head[previous], &mut tail[0])
+        } else {
+            let (head, tail) = self.cues.split_at_mut(previous);
+            (&mut tail[0], &mut head[current])
+        };
+
+        current_cue.effect.render(time, &mut self.frame);
+        previous_cue
+            .effect
+            .render(previous_cue.duration + time, &mut self.previous_frame);
+
+        let progress = (time.as_micros() * u128::from(u16::MAX))
+            .checked_div(transition.duration().as_micros())
+            .unwrap_or(0) as u16;
+
+        match transition {
+            Transition::Cut => {}
+            Transition::Crossfade { easing, .. } => {
+                let amount = (easing.apply(progress) >> 8) as u8;
+                for (pixel, previous_pixel) in self.frame.iter_mut().zip(&self.previous_frame) {
+                    *pixel = blend(*previous_pixel, *pixel, amount);
+                }
+            }
+            Transition::Wipe { easing, .. } => {
+                let wiped = (S * usize::from(easing.apply(progress))) / usize::from(u16::MAX);
+                self.frame[wiped..].copy_from_slice(&self.previous_frame[wiped..]);
+            }
+        }
+
+        &self.frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    const RED: LinSrgb<u8> = LinSrgb::new(200, 0, 0);
+    const BLUE: LinSrgb<u8> = LinSrgb::new(0, 0, 200);
+    const EASINGS: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    /// Renders a timeline that switches from red to blue after one second.
+    fn render_switch(transition: Transition, millis: &[u64]) -> Vec<[LinSrgb<u8>; 4]> {
+        let mut red = |_: Duration, pixels: &mut [LinSrgb<u8>]| pixels.fill(RED);
+        let mut blue = |_: Duration, pixels: &mut [LinSrgb<u8>]| pixels.fill(BLUE);
+
+        let mut timeline = Timeline::<2, 4>::new([
+            Cue::new(&mut red, Duration::from_secs(1)),
+            Cue::new(&mut blue, Duration::from_secs(10)).transition(transition),
+        ]);
+        timeline.start(Duration::ZERO);
+
+        millis
+            .iter()
+            .map(|&millis| *timeline.render(Duration::from_millis(millis)))
+            .collect()
+    }
+
+    #[test]
+    fn easing_endpoints() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0), 0, "{easing:?}");
+            assert_eq!(easing.apply(u16::MAX), u16::MAX, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn easing_curves() {
+        for x in (0..=u16::MAX).step_by(257) {
+            assert_eq!(Easing::Linear.apply(x), x);
+            assert!(Easing::EaseIn.apply(x) <= x);
+            assert!(Easing::EaseOut.apply(x) >= x);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0x8000), 0x8000);
+
+        for easing in EASINGS {
+            let mut previous = 0;
+            for x in (0..=u16::MAX).step_by(257) {
+                let eased = easing.apply(x);
+                assert!(eased >= previous, "{easing:?}");
+                previous = eased;
+            }
+        }
+    }
+
+    #[test]
+    fn cut() {
+        let frames = render_switch(Transition::Cut, &[0, 999, 1000, 5000]);
+        assert_eq!(frames, [[RED; 4], [RED; 4], [BLUE; 4], [BLUE; 4]]);
+    }
+
+    #[test]
+    fn crossfade() {
+        let transition = Transition::Crossfade {
+            duration: Duration::from_secs(2),
+            easing: Easing::Linear,
+        };
+        let frames = render_switch(transition, &[999, 1000, 2000, 3000]);
+
+        assert_eq!(frames[0], [RED; 4]);
+        assert_eq!(frames[1], [RED; 4]);
+        // Half way through the transition, both cues contribute equally
+        assert_eq!(frames[2], [LinSrgb::new(101, 0, 99); 4]);
+        assert_eq!(frames[3], [BLUE; 4]);
+    }
+
+    #[test]
+    fn wipe() {
+        let transition = Transition::Wipe {
+            duration: Duration::from_secs(4),
+            easing: Easing::Linear,
+        };
+        let frames = render_switch(transition, &[1000, 3500, 4999, 5000]);
+
+        assert_eq!(frames[0], [RED; 4]);
+        assert_eq!(frames[1], [BLUE, BLUE, RED, RED]);
+        assert_eq!(frames[2], [BLUE, BLUE, BLUE, RED]);
+        assert_eq!(frames[3], [BLUE; 4]);
+    }
+}
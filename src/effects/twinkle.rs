@@ -0,0 +1,121 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{blend, hash, smooth, triangle, Effect, BLACK};
+
+/// Randomly distributed pixels that fade in and out.
+///
+/// The pattern is derived from a hash of the pixel position and the time,
+/// so it doesn't require any state and is reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Twinkle {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    period: Duration,
+    density: u8,
+    seed: u32,
+}
+
+impl Twinkle {
+    /// Creates a twinkle effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color of the twinkling pixels
+    /// * `period` - The duration of a single twinkle
+    pub const fn new(color: LinSrgb<u8>, period: Duration) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            period,
+            density: 32,
+            seed: 0,
+        }
+    }
+
+    /// Sets the color of the pixels that don't twinkle. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Sets the probability of a pixel to twinkle per period,
+    /// from `0` (never) to `255` (almost always). Defaults to `32`.
+    pub const fn density(self, density: u8) -> Self {
+        Self { density, ..self }
+    }
+
+    /// Sets the seed of the random pattern, to make strips that show
+    /// the same effect look different. Defaults to `0`.
+    pub const fn seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+}
+
+impl Effect for Twinkle {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        let period = self.period.as_micros() as u64;
+        if period == 0 {
+            pixels.fill(self.background);
+            return;
+        }
+
+        let time = time.as_micros() as u64;
+        for (position, pixel) in pixels.iter_mut().enumerate() {
+            // Every pixel twinkles with its own phase offset
+            let pixel_seed = hash(position as u32 ^ self.seed);
+            let time = time + u64::from(pixel_seed) % period;
+
+            let cycle = time / period;
+            let lit = hash(pixel_seed ^ cycle as u32) & 0xFF < u32::from(self.density);
+
+            *pixel = if lit {
+                let phase = ((time % period) * 65536 / period) as u16;
+                blend(self.background, self.color, smooth(triangle(phase)))
+            } else {
+                self.background
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: LinSrgb<u8> = LinSrgb::new(255, 255, 255);
+    const BLUE: LinSrgb<u8> = LinSrgb::new(0, 0, 255);
+
+    fn render(twinkle: &mut Twinkle, millis: u64) -> [LinSrgb<u8>; 64] {
+        let mut pixels = [BLACK; 64];
+        twinkle.render(Duration::from_millis(millis), &mut pixels);
+        pixels
+    }
+
+    #[test]
+    fn reproducible() {
+        let mut twinkle = Twinkle::new(WHITE, Duration::from_secs(1)).density(128);
+
+        let pixels = render(&mut twinkle, 1234);
+        assert_eq!(render(&mut twinkle, 1234), pixels);
+        assert!(pixels.iter().any(|&pixel| pixel != BLACK));
+        assert!(pixels.iter().any(|&pixel| pixel == BLACK));
+        assert!(pixels
+            .iter()
+            .all(|pixel| pixel.red == pixel.green && pixel.green == pixel.blue));
+
+        let mut seeded = twinkle.seed(1);
+        assert_ne!(render(&mut seeded, 1234), pixels);
+    }
+
+    #[test]
+    fn idle_pixels_show_background() {
+        let mut twinkle = Twinkle::new(WHITE, Duration::from_secs(1))
+            .density(0)
+            .background(BLUE);
+        assert_eq!(render(&mut twinkle, 500), [BLUE; 64]);
+
+        let mut twinkle = Twinkle::new(WHITE, Duration::ZERO).background(BLUE);
+        assert_eq!(render(&mut twinkle, 500), [BLUE; 64]);
+    }
+}
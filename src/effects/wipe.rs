@@ -0,0 +1,82 @@
+use core::time::Duration;
+
+use palette::LinSrgb;
+
+use super::{phase, Effect, BLACK};
+
+/// Fills the strip with a color pixel by pixel, then clears it again the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorWipe {
+    color: LinSrgb<u8>,
+    background: LinSrgb<u8>,
+    duration: Duration,
+}
+
+impl ColorWipe {
+    /// Creates a color wipe.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color that gets wiped in
+    /// * `duration` - The time it takes to fill the entire strip
+    pub const fn new(color: LinSrgb<u8>, duration: Duration) -> Self {
+        Self {
+            color,
+            background: BLACK,
+            duration,
+        }
+    }
+
+    /// Sets the color that the strip gets cleared to. Defaults to black.
+    pub const fn background(self, background: LinSrgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+}
+
+impl Effect for ColorWipe {
+    fn render(&mut self, time: Duration, pixels: &mut [LinSrgb<u8>]) {
+        // One cycle consists of wiping the color in and wiping the background back in
+        let phase = phase(time, self.duration.saturating_mul(2));
+        let (progress, front, back) = if phase < 0x8000 {
+            (phase, self.color, self.background)
+        } else {
+            (phase - 0x8000, self.background, self.color)
+        };
+
+        let filled = (pixels.len() * usize::from(progress)) >> 15;
+        let (head, tail) = pixels.split_at_mut(filled.min(pixels.len()));
+        head.fill(front);
+        tail.fill(back);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: LinSrgb<u8> = LinSrgb::new(255, 0, 0);
+    const BLUE: LinSrgb<u8> = LinSrgb::new(0, 0, 255);
+
+    fn render(effect: &mut ColorWipe, millis: u64) -> [LinSrgb<u8>; 4] {
+        let mut pixels = [BLACK; 4];
+        effect.render(Duration::from_millis(millis), &mut pixels);
+        pixels
+    }
+
+    #[test]
+    fn wipes_in_and_out() {
+        let mut wipe = ColorWipe::new(RED, Duration::from_secs(1)).background(BLUE);
+
+        assert_eq!(render(&mut wipe, 0), [BLUE; 4]);
+        assert_eq!(render(&mut wipe, 500), [RED, RED, BLUE, BLUE]);
+        assert_eq!(render(&mut wipe, 1000), [RED; 4]);
+        assert_eq!(render(&mut wipe, 1500), [BLUE, BLUE, RED, RED]);
+        assert_eq!(render(&mut wipe, 2000), [BLUE; 4]);
+    }
+
+    #[test]
+    fn long_duration() {
+        let mut wipe = ColorWipe::new(RED, Duration::MAX);
+        assert_eq!(render(&mut wipe, 1000), [BLACK; 4]);
+    }
+}
@@ -12,3 +12,13 @@ pub enum WS2812InitError {
     /// The peripheral does not have enough timers for the given amount of pins.
     NotEnoughTimers,
 }
+
+/// Errors of the [PreprocessedPixels::try_prepare_pixels](crate::PreprocessedPixels::try_prepare_pixels) function
+#[derive(Debug, Snafu)]
+pub enum PrepareError {
+    /// The pixel data does not fit into the buffer and was truncated.
+    Truncated {
+        /// The capacity of the buffer, in pixels.
+        capacity: usize,
+    },
+}
@@ -10,7 +10,7 @@ use super::{
     maybe_own::MaybeOwn, InterruptHandler, InterruptHandlerData, PreprocessedPixels, WS2812Driver,
     WriteDmaResult,
 };
-use crate::{errors, pixelstream::PixelStreamRef, Pins};
+use crate::{errors, pixelstream::PixelStreamRef, Brightness, Pins};
 
 impl<const N: u8, const L: usize, PINS: Pins<N, L>> WS2812Driver<N, L, PINS>
 where
@@ -135,7 +135,11 @@ where
             finished_watcher: IdleTimerFinishedWatcher::new(flexio, Self::get_idle_timer_id()),
         });
 
-        Ok(Self { _pins: pins, inner })
+        Ok(Self {
+            _pins: pins,
+            inner,
+            brightness: [Brightness::FULL; L],
+        })
     }
 
     const fn get_shifter_id() -> u8 {
@@ -191,6 +195,26 @@ where
         }
     }
 
+    /// Sets the brightness of all LED strips for [`write()`](WS2812Driver::write).
+    ///
+    /// DMA based writes send preprocessed data; their brightness
+    /// is configured in the [`PreprocessedPixels`] buffer instead.
+    pub fn set_brightness(&mut self, brightness: Brightness) {
+        self.brightness = [brightness; L];
+    }
+
+    /// Sets the brightness of a single LED strip for [`write()`](WS2812Driver::write).
+    ///
+    /// DMA based writes send preprocessed data; their brightness
+    /// is configured in the [`PreprocessedPixels`] buffer instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`.
+    pub fn set_strip_brightness(&mut self, strip: usize, brightness: Brightness) {
+        self.brightness[strip] = brightness;
+    }
+
     /// Writes pixels to an LED strip.
     ///
     /// If the strips are of different length, the shorter ones will be padded
@@ -204,7 +228,7 @@ where
         self.inner.get().finished_watcher.clear();
 
         // Write data
-        for elem in InterleavedPixels::new(data) {
+        for elem in InterleavedPixels::new(data).with_brightness(self.brightness) {
             self.fill_shift_buffer(elem);
             while !self.shift_buffer_empty() {}
         }
@@ -224,12 +248,12 @@ where
     /// * `data` - Preprocessed pixel data to send to the LED strips
     /// * `dma` - The dma channel that should be used to transmit the data
     /// * `dma_signal_id` - The signal the FlexIO unit uses to communicate with the DMA.
-    ///                     This is chip specific and must therefore be supplied by the user.
-    ///                     The value can be found in the reference manual.
+    ///   This is chip specific and must therefore be supplied by the user.
+    ///   The value can be found in the reference manual.
     /// * `concurrent_action` - A function that will be executed while the pixels get transferred.
-    ///                         Typically used to render the next frame, so it can be
-    ///                         transmitted afterwards without a delay, to achieve the maximum possible
-    ///                         framerate.
+    ///   Typically used to render the next frame, so it can be
+    ///   transmitted afterwards without a delay, to achieve the maximum possible
+    ///   framerate.
     ///
     /// For technical reasons, an additional `[0, 0, 0]` pixel will be added at
     /// the of the transmission.
@@ -2,6 +2,9 @@ use imxrt_ral as ral;
 
 use ral::{flexio, Valid};
 
+// The FlexIO clock input the timings are based on.
+const FLEXIO_CLOCK_MHZ: u16 = 16;
+
 // A total cycle is 20 clock cycles. (16 MHz / 20 = 800 kHz)
 const CLOCK_DIVIDER: u8 = 10; // Timer toggles; meaning we need two cycles for one timer clock cycle, so this is half the total cycle length
 const LOW_BIT_CYCLES_ON: u8 = 5;
@@ -15,6 +18,11 @@ const LOW_BIT_CYCLES_OFF: u8 = CYCLE_LENGTH - LOW_BIT_CYCLES_ON;
 const HIGH_BIT_CYCLES_OFF: u8 = CYCLE_LENGTH - HIGH_BIT_CYCLES_ON;
 const LATCH_DELAY: u16 = CYCLE_LENGTH as u16 * LATCH_DELAY_PIXELS;
 
+/// The time it takes to transmit one byte, in microseconds.
+pub(crate) const BYTE_DURATION_US: u16 = 8 * CYCLE_LENGTH as u16 / FLEXIO_CLOCK_MHZ;
+/// The latch time after each transmission, in microseconds.
+pub(crate) const LATCH_DURATION_US: u16 = LATCH_DELAY / FLEXIO_CLOCK_MHZ;
+
 pub struct FlexIOConfigurator<const N: u8>
 where
     flexio::Instance<N>: Valid,
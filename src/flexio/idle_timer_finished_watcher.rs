@@ -77,7 +77,7 @@ impl<const N: u8> IdleTimerFinishedWatcher<N> {
         self.with_check_and_reset(|inner| inner.happened)
     }
 
-    pub fn finished(&self) -> IdleTimerFinished<N> {
+    pub fn finished(&self) -> IdleTimerFinished<'_, N> {
         IdleTimerFinished(self)
     }
 }
@@ -1,7 +1,10 @@
-use crate::pixelstream::PixelStreamRef;
+use crate::{brightness::Brightness, pixelstream::PixelStreamRef};
 
-fn spread4(x: u8) -> u32 {
-    let mut x = u32::from(x);
+/// The number of zero words that get appended after the pixel data.
+pub const TRAILING_ZEROS: u8 = 3;
+
+const fn compute_spread4(x: u8) -> u32 {
+    let mut x = x as u32;
 
     x = (x | (x << 12)) & 0x000F000F;
     x = (x | (x << 6)) & 0x03030303;
@@ -10,18 +13,87 @@ fn spread4(x: u8) -> u32 {
     x
 }
 
+/// Precomputed results of [`compute_spread4`] for all possible bytes.
+static SPREAD4_LUT: [u32; 256] = {
+    let mut lut = [0; 256];
+    let mut i = 0;
+    while i < lut.len() {
+        lut[i] = compute_spread4(i as u8);
+        i += 1;
+    }
+    lut
+};
+
+/// Spreads the bits of a byte to every fourth bit of a word.
+#[inline(always)]
+pub fn spread4(x: u8) -> u32 {
+    SPREAD4_LUT[usize::from(x)]
+}
+
+/// Spreads a byte to the bit positions of the given LED strip.
+#[inline(always)]
+pub fn spread4_lane(x: u8, lane: usize) -> u32 {
+    spread4(x) << (3 - lane)
+}
+
+/// Extracts the byte of the given LED strip from a data word.
+///
+/// The inverse of [`spread4_lane`].
+pub fn extract_lane(x: u32, lane: usize) -> u8 {
+    let mut x = (x >> (3 - lane)) & 0x11111111;
+
+    x = (x | (x >> 3)) & 0x03030303;
+    x = (x | (x >> 6)) & 0x000F000F;
+    x = (x | (x >> 12)) & 0x000000FF;
+
+    x as u8
+}
+
+/// The bits of a data word that belong to the given LED strip.
+pub fn lane_mask(lane: usize) -> u32 {
+    0x11111111 << (3 - lane)
+}
+
 pub struct InterleavedPixels<'a, const N: usize> {
     streams: [&'a mut dyn PixelStreamRef; N],
+    stream_lengths: [usize; N],
+    brightness: [Brightness; N],
     leftover_trailing_bytes: u8,
 }
 
 impl<'a, const N: usize> InterleavedPixels<'a, N> {
+    /// The number of streams that actually get transmitted; the hardware supports at most four.
+    const LANES: usize = if N < 4 { N } else { 4 };
+
     pub fn new(streams: [&'a mut dyn PixelStreamRef; N]) -> Self {
         Self {
             streams,
-            leftover_trailing_bytes: 3,
+            stream_lengths: [0; N],
+            brightness: [Brightness::FULL; N],
+            leftover_trailing_bytes: TRAILING_ZEROS,
         }
     }
+
+    /// Scales the data of every stream by the given brightness.
+    pub fn with_brightness(mut self, brightness: [Brightness; N]) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// The number of bytes that were read from each stream so far.
+    pub fn stream_lengths(&self) -> [usize; N] {
+        self.stream_lengths
+    }
+
+    /// Checks whether any of the streams still contains data.
+    ///
+    /// Consumes the next byte of the streams that are checked.
+    pub fn has_remaining_data(&mut self) -> bool {
+        self.streams
+            .iter_mut()
+            .take(Self::LANES)
+            .any(|stream| stream.next().is_some())
+    }
 }
 
 impl<const N: usize> Iterator for InterleavedPixels<'_, N> {
@@ -30,9 +102,12 @@ impl<const N: usize> Iterator for InterleavedPixels<'_, N> {
     fn next(&mut self) -> Option<Self::Item> {
         let mut has_next_data = false;
         let mut next_data = 0;
-        for (pos, stream) in self.streams.iter_mut().take(4).enumerate() {
-            if let Some(d) = stream.next() {
-                next_data |= spread4(d) << (3 - pos);
+        // `LANES` is known at compile time, so this loop gets unrolled
+        // for every strip count individually.
+        for pos in 0..Self::LANES {
+            if let Some(d) = self.streams[pos].next() {
+                next_data |= spread4_lane(self.brightness[pos].scale(d), pos);
+                self.stream_lengths[pos] += 1;
                 has_next_data = true;
             }
         }
@@ -47,3 +122,29 @@ impl<const N: usize> Iterator for InterleavedPixels<'_, N> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spread4_lut_matches_computation() {
+        for x in 0..=255 {
+            assert_eq!(spread4(x), compute_spread4(x));
+        }
+        assert_eq!(spread4(0b1000_0001), 0x1000_0001);
+        assert_eq!(spread4(0xFF), 0x1111_1111);
+    }
+
+    #[test]
+    fn extract_lane_is_inverse_of_spread4_lane() {
+        for lane in 0..4 {
+            for x in 0..=255 {
+                let word = spread4_lane(x, lane);
+                assert_eq!(word & !lane_mask(lane), 0);
+                assert_eq!(extract_lane(word, lane), x);
+                assert_eq!(extract_lane(word | !lane_mask(lane), lane), x);
+            }
+        }
+    }
+}
@@ -11,9 +11,10 @@ mod interrupt_handler;
 mod maybe_own;
 mod preprocessed_pixels;
 
-use crate::Pins;
+use crate::{Brightness, Pins};
 
-pub use preprocessed_pixels::PreprocessedPixels;
+pub(crate) use preprocessed_pixels::{transmission_duration, transmitted_words};
+pub use preprocessed_pixels::{PrepareSummary, PreprocessedPixels};
 
 use self::{idle_timer_finished_watcher::IdleTimerFinishedWatcher, maybe_own::MaybeOwn};
 
@@ -24,6 +25,7 @@ where
 {
     _pins: PINS,
     inner: MaybeOwn<InterruptHandlerData<N>>,
+    brightness: [Brightness; L],
 }
 
 /// The result of [WS2812Driver::write_dma()][WS2812Driver::write_dma].
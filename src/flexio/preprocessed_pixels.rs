@@ -1,6 +1,46 @@
-use crate::pixelstream::PixelStreamRef;
+use core::{ops::Range, time::Duration};
 
-use super::interleaved_pixels::InterleavedPixels;
+use crate::{
+    brightness::Brightness,
+    errors,
+    pixel::Pixel,
+    pixelstream::PixelStreamRef,
+    power::{PowerModel, PowerReport},
+};
+
+use super::{
+    flexio_configurator::{BYTE_DURATION_US, LATCH_DURATION_US},
+    interleaved_pixels::{
+        extract_lane, lane_mask, spread4, spread4_lane, InterleavedPixels, TRAILING_ZEROS,
+    },
+};
+
+/// The time it takes to transmit the given number of bytes to each LED strip.
+///
+/// Includes the latch time at the end of the transmission.
+pub(crate) fn transmission_duration(bytes: usize) -> Duration {
+    let transmission = bytes as u64 * u64::from(BYTE_DURATION_US);
+    Duration::from_micros(transmission + u64::from(LATCH_DURATION_US))
+}
+
+/// The number of words that get transmitted for a strip of the given length, in bytes.
+///
+/// Consists of the pixel data, the trailing zeros and the zero termination of
+/// [`PreprocessedPixels`], assuming that the data fits into the buffer.
+pub(crate) fn transmitted_words(strip_bytes: usize, bytes_per_pixel: usize) -> usize {
+    strip_bytes + usize::from(TRAILING_ZEROS) + bytes_per_pixel
+}
+
+/// Information about the data written by [`PreprocessedPixels::prepare_pixels()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrepareSummary<const L: usize> {
+    /// The number of bytes written for each LED strip.
+    pub strip_lengths: [usize; L],
+    /// True if the pixel data exceeded the capacity of the buffer and was cut off.
+    pub truncated: bool,
+    /// The time it takes to transmit the prepared data to the LED strips.
+    pub frame_duration: Duration,
+}
 
 /// A buffer that preprocesses pixel data for FlexIO DMA usage.
 ///
@@ -8,12 +48,17 @@ use super::interleaved_pixels::InterleavedPixels;
 ///
 /// * `N` - the number of pixels the buffer can hold
 /// * `L` - the number of LED strips
-/// * `P` - the number of bytes per pixel
+/// * `P` - the number of bytes per pixel. Can be derived from the pixel type via
+///   [`Pixel::BYTES`], like `PreprocessedPixels<N, L, { <MyPixel as Pixel>::BYTES }>`.
 #[derive(Debug)]
 #[repr(C, align(4))]
 pub struct PreprocessedPixels<const N: usize, const L: usize, const P: usize = 3> {
     /// Start with a `u32`, for 32bit alignment
     len: u32,
+    /// The number of bytes of each individual LED strip.
+    strip_lengths: [u32; L],
+    /// The brightness of each individual LED strip.
+    brightness: [Brightness; L],
     /// The data. Would ideally be `[u32; P*N]`, but const expressions aren't there yet.
     /// So we need to trick it with a pointer reinterpret cast later.
     ///
@@ -31,6 +76,8 @@ impl<const N: usize, const L: usize, const P: usize> PreprocessedPixels<N, L, P>
     pub const fn new() -> Self {
         Self {
             len: 0,
+            strip_lengths: [0; L],
+            brightness: [Brightness::FULL; L],
             data: [[0; P]; N],
             zero_termination: [0; P],
         }
@@ -41,6 +88,35 @@ impl<const N: usize, const L: usize, const P: usize> PreprocessedPixels<N, L, P>
         N
     }
 
+    /// Sets the brightness of all LED strips.
+    ///
+    /// The brightness gets applied while preparing pixels;
+    /// data that is already prepared stays unaffected.
+    pub fn set_brightness(&mut self, brightness: Brightness) {
+        self.brightness = [brightness; L];
+    }
+
+    /// Sets the brightness of a single LED strip.
+    ///
+    /// The brightness gets applied while preparing pixels;
+    /// data that is already prepared stays unaffected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`.
+    pub fn set_strip_brightness(&mut self, strip: usize, brightness: Brightness) {
+        self.brightness[strip] = brightness;
+    }
+
+    /// The brightness of the given LED strip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`.
+    pub fn strip_brightness(&self, strip: usize) -> Brightness {
+        self.brightness[strip]
+    }
+
     fn get_data_mut(&mut self) -> &mut [u32] {
         let ptr = self.data.as_mut_ptr().cast();
         let len = P * N;
@@ -62,12 +138,27 @@ impl<const N: usize, const L: usize, const P: usize> PreprocessedPixels<N, L, P>
         unsafe { core::slice::from_raw_parts(ptr, len) }
     }
 
+    /// The time it takes to transmit the current data to the LED strips.
+    ///
+    /// Includes the latch time at the end of the transmission.
+    pub fn frame_duration(&self) -> Duration {
+        transmission_duration(self.get_dma_data().len())
+    }
+
     /// Prepares a set of pixels for transmission to the LED strip.
-    pub fn prepare_pixels(&mut self, pixels: [&mut dyn PixelStreamRef; L]) {
+    ///
+    /// Pixel data that exceeds the capacity of the buffer will be dropped;
+    /// this is reported in the returned summary.
+    /// Use [`try_prepare_pixels()`](PreprocessedPixels::try_prepare_pixels) to
+    /// treat this as an error instead.
+    pub fn prepare_pixels(&mut self, pixels: [&mut dyn PixelStreamRef; L]) -> PrepareSummary<L> {
+        let brightness = self.brightness;
         let data = self.get_data_mut();
 
+        let mut interleaved = InterleavedPixels::new(pixels).with_brightness(brightness);
+
         let mut len = 0;
-        for (d, pixel) in data.iter_mut().zip(InterleavedPixels::new(pixels)) {
+        for (d, pixel) in data.iter_mut().zip(&mut interleaved) {
             *d = pixel;
             len += 1;
         }
@@ -75,6 +166,390 @@ impl<const N: usize, const L: usize, const P: usize> PreprocessedPixels<N, L, P>
         data[len..].fill(0);
 
         self.len = len as u32;
+        let strip_lengths = interleaved.stream_lengths();
+        for (strip_length, stream_length) in self.strip_lengths.iter_mut().zip(strip_lengths) {
+            *strip_length = stream_length as u32;
+        }
+
+        PrepareSummary {
+            strip_lengths,
+            truncated: interleaved.has_remaining_data(),
+            frame_duration: self.frame_duration(),
+        }
+    }
+
+    /// Same as [`prepare_pixels()`](PreprocessedPixels::prepare_pixels), but fails
+    /// if the pixel data exceeds the capacity of the buffer.
+    ///
+    /// Note that the buffer still contains the truncated data if this function fails.
+    pub fn try_prepare_pixels(
+        &mut self,
+        pixels: [&mut dyn PixelStreamRef; L],
+    ) -> Result<PrepareSummary<L>, errors::PrepareError> {
+        let summary = self.prepare_pixels(pixels);
+
+        if summary.truncated {
+            Err(errors::PrepareError::Truncated { capacity: N })
+        } else {
+            Ok(summary)
+        }
+    }
+
+    /// Prepares a set of raw byte slices for transmission to the LED strips.
+    ///
+    /// This is a faster alternative to [`prepare_pixels()`](PreprocessedPixels::prepare_pixels)
+    /// for data that is already in the byte order of the LED strips (GRB for WS2812),
+    /// as it avoids the per-byte overhead of pixel streams.
+    ///
+    /// Bytes that exceed the capacity of the buffer will be dropped;
+    /// this is reported in the returned summary.
+    pub fn prepare_bytes(&mut self, strips: [&[u8]; L]) -> PrepareSummary<L> {
+        let previous_len = self.len as usize;
+        let brightness = self.brightness;
+        let data = self.get_data_mut();
+
+        // Everything behind `len` is already zero
+        data[..previous_len].fill(0);
+
+        let mut truncated = false;
+        let mut strip_lengths = [0; L];
+        for (lane, ((bytes, strip_length), brightness)) in strips
+            .iter()
+            .zip(strip_lengths.iter_mut())
+            .zip(brightness)
+            .take(4)
+            .enumerate()
+        {
+            if brightness == Brightness::FULL {
+                for (d, &byte) in data.iter_mut().zip(bytes.iter()) {
+                    *d |= spread4_lane(byte, lane);
+                }
+            } else {
+                for (d, &byte) in data.iter_mut().zip(bytes.iter()) {
+                    *d |= spread4_lane(brightness.scale(byte), lane);
+                }
+            }
+            *strip_length = bytes.len().min(data.len());
+            truncated |= bytes.len() > data.len();
+        }
+
+        for (strip_length, bytes) in self.strip_lengths.iter_mut().zip(strip_lengths) {
+            *strip_length = bytes as u32;
+        }
+        self.update_len();
+
+        PrepareSummary {
+            strip_lengths,
+            truncated,
+            frame_duration: self.frame_duration(),
+        }
+    }
+
+    /// Prepares a single set of pixels for transmission to multiple LED strips.
+    ///
+    /// All selected LED strips display the same pixels, all other LED strips stay dark.
+    /// In contrast to passing identical streams to [`prepare_pixels()`](PreprocessedPixels::prepare_pixels),
+    /// the pixels only get read and encoded once, which divides the preparation time
+    /// by the number of selected strips.
+    ///
+    /// Pixel data that exceeds the capacity of the buffer will be dropped;
+    /// this is reported in the returned summary.
+    ///
+    /// # Arguments
+    ///
+    /// * `pixels` - The pixels to display
+    /// * `lanes` - Which of the LED strips should display the pixels
+    pub fn prepare_mirrored(
+        &mut self,
+        pixels: &mut dyn PixelStreamRef,
+        lanes: [bool; L],
+    ) -> PrepareSummary<L> {
+        let previous_len = self.len as usize;
+        let brightness = self.brightness;
+
+        let selected = || {
+            lanes
+                .iter()
+                .zip(brightness)
+                .take(4)
+                .enumerate()
+                .filter(|(_, (&selected, _))| selected)
+                .map(|(lane, (_, brightness))| (lane, brightness))
+        };
+
+        // Spread data occupies every fourth bit, so multiplying it with the
+        // lowest bit of every selected lane places it on all of those lanes at once.
+        let multiplier: u32 = selected().map(|(lane, _)| 1 << (3 - lane)).sum();
+        let common_brightness = selected().next().map_or(Brightness::FULL, |(_, b)| b);
+        let uniform = selected().all(|(_, brightness)| brightness == common_brightness);
+
+        let data = self.get_data_mut();
+
+        let mut length = 0;
+        for d in data.iter_mut() {
+            let Some(byte) = pixels.next() else {
+                break;
+            };
+            *d = if uniform {
+                spread4(common_brightness.scale(byte)) * multiplier
+            } else {
+                selected().fold(0, |word, (lane, brightness)| {
+                    word | spread4_lane(brightness.scale(byte), lane)
+                })
+            };
+            length += 1;
+        }
+
+        let truncated = length == data.len() && pixels.next().is_some();
+
+        // Everything behind the previous `len` is already zero
+        if previous_len > length {
+            data[length..previous_len].fill(0);
+        }
+
+        let mut strip_lengths = [0; L];
+        for (lane, _) in selected() {
+            strip_lengths[lane] = length;
+        }
+        for (strip_length, bytes) in self.strip_lengths.iter_mut().zip(strip_lengths) {
+            *strip_length = bytes as u32;
+        }
+        self.update_len();
+
+        PrepareSummary {
+            strip_lengths,
+            truncated,
+            frame_duration: self.frame_duration(),
+        }
+    }
+
+    /// Prepares the pixels of a single LED strip for transmission.
+    ///
+    /// In contrast to [`prepare_pixels()`](PreprocessedPixels::prepare_pixels), this
+    /// only replaces the data of the given strip; the data of all other strips stays untouched.
+    /// This allows the strips to be rendered independently of each other.
+    ///
+    /// The length of the transmission is determined by the longest strip.
+    ///
+    /// Pixel data that exceeds the capacity of the buffer will be dropped;
+    /// this is reported in the returned summary, which covers all strips.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The index of the LED strip
+    /// * `pixels` - The new pixels of the LED strip
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`.
+    pub fn prepare_strip(
+        &mut self,
+        strip: usize,
+        pixels: &mut dyn PixelStreamRef,
+    ) -> PrepareSummary<L> {
+        assert!(strip < L, "Strip index out of range");
+
+        let previous_length = self.strip_lengths[strip] as usize;
+        let brightness = self.brightness[strip];
+        let mask = lane_mask(strip);
+        let data = self.get_data_mut();
+
+        let mut length = 0;
+        for d in data.iter_mut() {
+            let Some(byte) = pixels.next() else {
+                break;
+            };
+            *d = (*d & !mask) | spread4_lane(brightness.scale(byte), strip);
+            length += 1;
+        }
+
+        let truncated = length == data.len() && pixels.next().is_some();
+
+        // Clear leftover data of the previous frame
+        for d in data.iter_mut().take(previous_length).skip(length) {
+            *d &= !mask;
+        }
+
+        self.strip_lengths[strip] = length as u32;
+        self.update_len();
+
+        PrepareSummary {
+            strip_lengths: self.strip_lengths.map(|length| length as usize),
+            truncated,
+            frame_duration: self.frame_duration(),
+        }
+    }
+
+    /// Sets a single pixel of a single LED strip.
+    ///
+    /// Only the data of the given pixel gets modified, the rest of the buffer stays untouched.
+    /// If the pixel is located behind the current end of the data, the data gets extended
+    /// and the pixels in between will be black.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The index of the LED strip
+    /// * `index` - The position of the pixel on the LED strip
+    /// * `pixel` - The new pixel value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`, `index` is not smaller than `N`,
+    /// or the pixel doesn't consist of exactly `P` bytes.
+    pub fn set_pixel<Px: Pixel>(&mut self, strip: usize, index: usize, pixel: Px) {
+        self.fill_range(strip, index..index + 1, pixel);
+    }
+
+    /// Sets a range of pixels of a single LED strip to the same value.
+    ///
+    /// Only the data of the given pixels gets modified, the rest of the buffer stays untouched.
+    /// If the range reaches behind the current end of the data, the data gets extended
+    /// and the pixels in between will be black.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The index of the LED strip
+    /// * `range` - The positions of the pixels on the LED strip
+    /// * `pixel` - The new pixel value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strip` is not smaller than `L`, `range` reaches beyond `N`,
+    /// or the pixel doesn't consist of exactly `P` bytes.
+    pub fn fill_range<Px: Pixel>(&mut self, strip: usize, range: Range<usize>, pixel: Px) {
+        assert!(strip < L, "Strip index out of range");
+        assert_eq!(Px::BYTES, P, "Pixel size doesn't match the buffer");
+
+        let brightness = self.brightness[strip];
+        let mut words = [0; P];
+        for (word, byte) in words.iter_mut().zip(pixel.into_ws2812_bytes()) {
+            *word = spread4_lane(brightness.scale(byte), strip);
+        }
+
+        if range.is_empty() {
+            return;
+        }
+
+        let mask = lane_mask(strip);
+        let end = range.end;
+        for data in &mut self.data[range] {
+            for (d, word) in data.iter_mut().zip(words) {
+                *d = (*d & !mask) | word;
+            }
+        }
+
+        let strip_length = &mut self.strip_lengths[strip];
+        *strip_length = (*strip_length).max((end * P) as u32);
+        self.update_len();
+    }
+
+    /// Estimates the current the LED strips draw while displaying the current data.
+    ///
+    /// # Arguments
+    ///
+    /// * `models` - The power model of each LED strip
+    ///
+    /// # Returns
+    ///
+    /// The estimated current, in milliamps.
+    pub fn estimate_current(&self, models: &[PowerModel; L]) -> u32 {
+        let (idle, channel_sum) = self.current_microamps(models);
+        ((idle + channel_sum / 255) / 1000) as u32
+    }
+
+    /// Scales the current data down, so that it stays within the given current budget.
+    ///
+    /// Intended to be called directly after preparing the pixels of a frame.
+    /// Data that already fits into the budget stays untouched.
+    ///
+    /// The limited current never exceeds the budget, unless the idle current
+    /// of the LEDs alone exceeds it; in that case, all LEDs get turned off.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget_milliamps` - The maximum current the LED strips may draw in total
+    /// * `models` - The power model of each LED strip
+    pub fn limit_current(
+        &mut self,
+        budget_milliamps: u32,
+        models: &[PowerModel; L],
+    ) -> PowerReport {
+        let (idle, channel_sum) = self.current_microamps(models);
+        let estimated_milliamps = ((idle + channel_sum / 255) / 1000) as u32;
+
+        let budget = u64::from(budget_milliamps) * 1000;
+        if idle + channel_sum / 255 <= budget {
+            return PowerReport {
+                estimated_milliamps,
+                scaling: Brightness::FULL,
+                limited_milliamps: estimated_milliamps,
+            };
+        }
+
+        // The idle current can't be reduced, so only scale the dynamic part.
+        // Every value gets multiplied with `factor / 256` and rounded down,
+        // which keeps the dynamic current below `available`. In contrast to
+        // `Brightness::scale()`, small values may drop to zero here; otherwise
+        // dim frames could never be reduced.
+        let available = budget.saturating_sub(idle);
+        let factor = (available * 255 * 256)
+            .checked_div(channel_sum)
+            .unwrap_or(0)
+            .min(255) as u32;
+
+        let len = self.len as usize;
+        let data = self.get_data_mut();
+        for d in &mut data[..len] {
+            let mut scaled = 0;
+            for lane in 0..L.min(4) {
+                let value = (u32::from(extract_lane(*d, lane)) * factor) >> 8;
+                scaled |= spread4_lane(value as u8, lane);
+            }
+            *d = scaled;
+        }
+
+        let (idle, channel_sum) = self.current_microamps(models);
+        PowerReport {
+            estimated_milliamps,
+            scaling: Brightness::new(factor.saturating_sub(1) as u8),
+            limited_milliamps: ((idle + channel_sum / 255) / 1000) as u32,
+        }
+    }
+
+    /// Computes the idle current of the current data, in microamps, and the
+    /// sum of all channel values weighted by their current at full duty.
+    ///
+    /// The dynamic current in microamps is the weighted sum divided by 255;
+    /// it is kept undivided so that the current can be limited without rounding errors.
+    fn current_microamps(&self, models: &[PowerModel; L]) -> (u64, u64) {
+        let data = self.get_dma_data();
+
+        let mut idle = 0;
+        let mut channel_sum = 0;
+        for (lane, (model, &strip_length)) in models
+            .iter()
+            .zip(self.strip_lengths.iter())
+            .take(4)
+            .enumerate()
+        {
+            let strip_length = strip_length as usize;
+            let num_leds = strip_length.div_ceil(P);
+            idle += num_leds as u64 * u64::from(model.idle_microamps);
+
+            for (pos, &d) in data.iter().take(strip_length).enumerate() {
+                let channel_microamps = model.channel_microamps.get(pos % P).copied();
+                channel_sum +=
+                    u64::from(extract_lane(d, lane)) * u64::from(channel_microamps.unwrap_or(0));
+            }
+        }
+
+        (idle, channel_sum)
+    }
+
+    /// Recomputes the length of the transmission from the lengths of the individual strips.
+    fn update_len(&mut self) {
+        let longest_strip = self.strip_lengths.iter().copied().max().unwrap_or(0) as usize;
+        self.len = (longest_strip + usize::from(TRAILING_ZEROS)).min(P * N) as u32;
     }
 }
 
@@ -83,3 +558,366 @@ impl<const N: usize, const P: usize> Default for PreprocessedPixels<N, P> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::pixelstream::{stream_refs, IntoPixelStream};
+
+    /// Decodes the bytes of a single LED strip from the transmitted data.
+    ///
+    /// Note that `[u8; 3]` pixels are RGB, so they get transmitted as GRB.
+    fn lane_bytes<const N: usize, const L: usize, const P: usize>(
+        pixels: &PreprocessedPixels<N, L, P>,
+        lane: usize,
+    ) -> Vec<u8> {
+        pixels
+            .get_dma_data()
+            .iter()
+            .map(|&d| extract_lane(d, lane))
+            .collect()
+    }
+
+    #[test]
+    fn set_pixel_extends_data() {
+        let mut pixels = PreprocessedPixels::<8, 2>::new();
+
+        pixels.set_pixel(1, 2, [1, 2, 3]);
+
+        assert_eq!(pixels.strip_lengths, [0, 9]);
+        assert_eq!(pixels.len, 9 + u32::from(TRAILING_ZEROS));
+        assert_eq!(
+            lane_bytes(&pixels, 1),
+            [0, 0, 0, 0, 0, 0, 2, 1, 3, 0, 0, 0, 0, 0, 0]
+        );
+        assert!(lane_bytes(&pixels, 0).iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn fill_range_keeps_other_data() {
+        let mut pixels = PreprocessedPixels::<8, 2>::new();
+
+        pixels.fill_range(0, 0..4, [9, 9, 9]);
+        pixels.fill_range(0, 1..2, [1, 2, 3]);
+        pixels.set_pixel(1, 0, [7, 7, 7]);
+
+        assert_eq!(pixels.strip_lengths, [12, 3]);
+        assert_eq!(pixels.len, 12 + u32::from(TRAILING_ZEROS));
+        assert_eq!(
+            lane_bytes(&pixels, 0)[..12],
+            [9, 9, 9, 2, 1, 3, 9, 9, 9, 9, 9, 9]
+        );
+        assert_eq!(lane_bytes(&pixels, 1)[..4], [7, 7, 7, 0]);
+    }
+
+    #[test]
+    #[should_panic = "Pixel size doesn't match the buffer"]
+    fn set_pixel_rejects_larger_pixels() {
+        PreprocessedPixels::<4, 1>::new().set_pixel(0, 0, crate::Rgb16::new(1, 2, 3));
+    }
+
+    #[test]
+    #[should_panic = "Pixel size doesn't match the buffer"]
+    fn fill_range_rejects_smaller_pixels() {
+        PreprocessedPixels::<4, 1, 6>::new().fill_range(0, 0..2, [1, 2, 3]);
+    }
+
+    #[test]
+    fn fill_range_empty_is_noop() {
+        let mut pixels = PreprocessedPixels::<8, 1>::new();
+
+        pixels.fill_range(0, 5..5, [1, 2, 3]);
+
+        assert_eq!(pixels.strip_lengths, [0]);
+        assert_eq!(pixels.len, 0);
+    }
+
+    #[test]
+    fn fill_range_len_capped_at_capacity() {
+        let mut pixels = PreprocessedPixels::<4, 1>::new();
+
+        pixels.fill_range(0, 0..4, [1, 2, 3]);
+
+        assert_eq!(pixels.strip_lengths, [12]);
+        assert_eq!(pixels.len, 12);
+        assert_eq!(pixels.get_dma_data().len(), 12 + 3);
+    }
+
+    #[test]
+    fn fill_range_applies_brightness() {
+        let mut pixels = PreprocessedPixels::<4, 1>::new();
+        pixels.set_brightness(Brightness::new(127));
+
+        pixels.set_pixel(0, 0, [255, 100, 0]);
+
+        assert_eq!(lane_bytes(&pixels, 0)[..3], [50, 127, 0]);
+    }
+
+    #[test]
+    fn prepare_strip_keeps_other_strips() {
+        let mut pixels = PreprocessedPixels::<8, 2>::new();
+        pixels.fill_range(1, 0..2, [7, 7, 7]);
+
+        pixels.prepare_strip(0, &mut [[1, 2, 3]; 3].into_pixel_stream());
+
+        assert_eq!(pixels.strip_lengths, [9, 6]);
+        assert_eq!(pixels.len, 9 + u32::from(TRAILING_ZEROS));
+        assert_eq!(lane_bytes(&pixels, 0)[..10], [2, 1, 3, 2, 1, 3, 2, 1, 3, 0]);
+        assert_eq!(lane_bytes(&pixels, 1)[..7], [7, 7, 7, 7, 7, 7, 0]);
+    }
+
+    #[test]
+    fn prepare_strip_clears_leftover_data() {
+        let mut pixels = PreprocessedPixels::<8, 2>::new();
+        pixels.prepare_strip(0, &mut [[1, 2, 3]; 4].into_pixel_stream());
+        pixels.set_pixel(1, 1, [7, 7, 7]);
+
+        pixels.prepare_strip(0, &mut [[4, 5, 6]; 1].into_pixel_stream());
+
+        assert_eq!(pixels.strip_lengths, [3, 6]);
+        assert_eq!(pixels.len, 6 + u32::from(TRAILING_ZEROS));
+        assert_eq!(lane_bytes(&pixels, 0), [5, 4, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(lane_bytes(&pixels, 1)[..6], [0, 0, 0, 7, 7, 7]);
+
+        pixels.prepare_strip(1, &mut core::iter::empty::<[u8; 3]>().into_pixel_stream());
+
+        assert_eq!(pixels.strip_lengths, [3, 0]);
+        assert_eq!(pixels.len, 3 + u32::from(TRAILING_ZEROS));
+        assert!(pixels.data.iter().flatten().all(|&d| d & lane_mask(1) == 0));
+    }
+
+    #[test]
+    fn transmitted_words_match_dma_data() {
+        let mut pixels = PreprocessedPixels::<16, 2>::new();
+        pixels.prepare_pixels([
+            &mut [[1u8, 2, 3]; 2].into_pixel_stream(),
+            &mut [[4u8, 5, 6]; 3].into_pixel_stream(),
+        ]);
+
+        assert_eq!(pixels.get_dma_data().len(), transmitted_words(9, 3));
+    }
+
+    #[test]
+    fn transmission_duration_matches_ws2812_timing() {
+        // 1.25us per bit, 300us latch time
+        assert_eq!(transmission_duration(0), Duration::from_micros(300));
+        assert_eq!(transmission_duration(3), Duration::from_micros(330));
+    }
+
+    #[test]
+    fn prepare_pixels_reports_strip_lengths() {
+        let mut pixels = PreprocessedPixels::<8, 2>::new();
+
+        let summary = pixels.prepare_pixels([
+            &mut [[1, 2, 3]; 2].into_pixel_stream(),
+            &mut [[1, 2, 3]; 5].into_pixel_stream(),
+        ]);
+
+        assert_eq!(summary.strip_lengths, [6, 15]);
+        assert!(!summary.truncated);
+        assert_eq!(summary.frame_duration, pixels.frame_duration());
+        assert_eq!(pixels.strip_lengths, [6, 15]);
+        assert_eq!(pixels.len, 15 + u32::from(TRAILING_ZEROS));
+    }
+
+    #[test]
+    fn prepare_pixels_reports_truncation() {
+        let mut pixels = PreprocessedPixels::<2, 1>::new();
+
+        let summary = pixels.prepare_pixels([&mut [[1, 2, 3]; 3].into_pixel_stream()]);
+
+        assert_eq!(summary.strip_lengths, [6]);
+        assert!(summary.truncated);
+        assert_eq!(pixels.get_dma_data().len(), 6 + 3);
+
+        let summary = pixels.prepare_pixels([&mut [[1, 2, 3]; 2].into_pixel_stream()]);
+        assert!(!summary.truncated);
+    }
+
+    #[test]
+    fn try_prepare_pixels_fails_on_truncation() {
+        let mut pixels = PreprocessedPixels::<2, 1>::new();
+
+        assert!(pixels
+            .try_prepare_pixels([&mut [[1, 2, 3]; 2].into_pixel_stream()])
+            .is_ok());
+        assert!(matches!(
+            pixels.try_prepare_pixels([&mut [[1, 2, 3]; 3].into_pixel_stream()]),
+            Err(errors::PrepareError::Truncated { capacity: 2 })
+        ));
+    }
+
+    #[test]
+    fn prepare_strip_reports_truncation() {
+        let mut pixels = PreprocessedPixels::<2, 2>::new();
+        pixels.set_pixel(1, 0, [7, 7, 7]);
+
+        let summary = pixels.prepare_strip(0, &mut [[1, 2, 3]; 2].into_pixel_stream());
+        assert_eq!(summary.strip_lengths, [6, 3]);
+        assert!(!summary.truncated);
+
+        let summary = pixels.prepare_strip(0, &mut [[1, 2, 3]; 3].into_pixel_stream());
+        assert_eq!(summary.strip_lengths, [6, 3]);
+        assert!(summary.truncated);
+    }
+
+    #[test]
+    fn prepare_bytes_matches_prepare_pixels() {
+        let strip0 = [[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+        let strip1 = [[255, 128, 0]; 2];
+
+        let mut expected = PreprocessedPixels::<4, 2>::new();
+        expected.set_strip_brightness(1, Brightness::new(100));
+        let expected_summary = expected.prepare_pixels([
+            &mut strip0.into_pixel_stream(),
+            &mut strip1.into_pixel_stream(),
+        ]);
+
+        let bytes0 = [2, 1, 3, 5, 4, 6, 8, 7, 9];
+        let bytes1 = [128, 255, 0, 128, 255, 0];
+        let mut pixels = PreprocessedPixels::<4, 2>::new();
+        pixels.set_strip_brightness(1, Brightness::new(100));
+        pixels.fill_range(0, 0..4, [9, 9, 9]);
+        let summary = pixels.prepare_bytes([&bytes0, &bytes1]);
+
+        assert_eq!(summary, expected_summary);
+        assert_eq!(pixels.len, expected.len);
+        assert_eq!(pixels.strip_lengths, expected.strip_lengths);
+        assert_eq!(pixels.data, expected.data);
+    }
+
+    #[test]
+    fn prepare_bytes_reports_truncation() {
+        let mut pixels = PreprocessedPixels::<1, 2>::new();
+
+        let summary = pixels.prepare_bytes([&[1, 2, 3, 4], &[1]]);
+
+        assert_eq!(summary.strip_lengths, [3, 1]);
+        assert!(summary.truncated);
+        assert_eq!(pixels.len, 3);
+    }
+
+    #[test]
+    fn limit_current_stays_within_budget() {
+        let mut pixels = PreprocessedPixels::<100, 1>::new();
+        pixels.fill_range(0, 0..100, [255, 255, 255]);
+
+        let models = [PowerModel::WS2812];
+        assert_eq!(pixels.estimate_current(&models), 6100);
+
+        let report = pixels.limit_current(2000, &models);
+        assert_eq!(report.estimated_milliamps, 6100);
+        assert!(report.limited_milliamps <= 2000, "{report:?}");
+        assert!(report.limited_milliamps > 1900, "{report:?}");
+        assert_eq!(pixels.estimate_current(&models), report.limited_milliamps);
+    }
+
+    #[test]
+    fn limit_current_scales_dim_frames() {
+        let mut pixels = PreprocessedPixels::<100, 1>::new();
+        pixels.fill_range(0, 0..100, [1, 1, 1]);
+
+        let models = [PowerModel::uniform(20_000, 0)];
+        let report = pixels.limit_current(10, &models);
+
+        assert_eq!(report.estimated_milliamps, 23);
+        assert!(report.limited_milliamps <= 10, "{report:?}");
+    }
+
+    #[test]
+    fn limit_current_keeps_frames_that_fit() {
+        let mut pixels = PreprocessedPixels::<10, 2>::new();
+        pixels.fill_range(0, 0..10, [255, 0, 0]);
+        pixels.fill_range(1, 0..5, [0, 0, 255]);
+
+        let models = [PowerModel::WS2812; 2];
+        let report = pixels.limit_current(1000, &models);
+
+        assert_eq!(report.scaling, Brightness::FULL);
+        assert_eq!(report.estimated_milliamps, 315);
+        assert_eq!(report.limited_milliamps, 315);
+        assert_eq!(lane_bytes(&pixels, 0)[..3], [0, 255, 0]);
+    }
+
+    #[test]
+    fn limit_current_budget_below_idle() {
+        let mut pixels = PreprocessedPixels::<10, 1>::new();
+        pixels.fill_range(0, 0..10, [255, 255, 255]);
+
+        let report = pixels.limit_current(5, &[PowerModel::WS2812]);
+
+        assert_eq!(report.limited_milliamps, 10);
+        assert!(lane_bytes(&pixels, 0).iter().all(|&b| b == 0));
+    }
+
+    /// Checks `prepare_mirrored()` against `prepare_pixels()` with a copy of
+    /// the pixels for every selected lane, starting from a dirty buffer.
+    fn check_mirrored<const N: usize, const L: usize>(
+        pixels: &[[u8; 3]],
+        lanes: [bool; L],
+        brightness: [Brightness; L],
+    ) -> PrepareSummary<L> {
+        let prepare = |mirrored: bool| {
+            let mut buffer = PreprocessedPixels::<N, L>::new();
+            for (lane, &brightness) in brightness.iter().enumerate() {
+                buffer.set_strip_brightness(lane, brightness);
+            }
+            buffer.fill_range(0, 0..N, [0xFFu8; 3]);
+
+            let summary = if mirrored {
+                buffer.prepare_mirrored(&mut pixels.iter().copied().into_pixel_stream(), lanes)
+            } else {
+                let mut streams: [_; L] = core::array::from_fn(|lane| {
+                    let lane_pixels = if lanes[lane] { pixels } else { &[] };
+                    lane_pixels.iter().copied().into_pixel_stream()
+                });
+                buffer.prepare_pixels(stream_refs(&mut streams))
+            };
+            (summary, buffer.get_dma_data().to_vec())
+        };
+
+        let (summary, data) = prepare(true);
+        let (expected_summary, expected_data) = prepare(false);
+        assert_eq!(summary, expected_summary);
+        assert_eq!(data, expected_data);
+        summary
+    }
+
+    #[test]
+    fn prepare_mirrored_matches_cloned_streams() {
+        let pixels = [[1u8, 2, 3], [4, 5, 6], [0x80, 0x7F, 0xFF]];
+        let dimmed = Brightness::new(100);
+
+        let summary =
+            check_mirrored::<8, 4>(&pixels, [true, false, true, true], [Brightness::FULL; 4]);
+        assert_eq!(summary.strip_lengths, [9, 0, 9, 9]);
+        assert!(!summary.truncated);
+
+        let summary = check_mirrored::<8, 4>(
+            &pixels,
+            [false, true, true, false],
+            [Brightness::FULL, dimmed, Brightness::FULL, dimmed],
+        );
+        assert_eq!(summary.strip_lengths, [0, 9, 9, 0]);
+
+        let summary = check_mirrored::<8, 3>(&pixels, [true, false, true], [dimmed; 3]);
+        assert_eq!(summary.strip_lengths, [9, 0, 9]);
+
+        let summary = check_mirrored::<8, 1>(&pixels, [true], [Brightness::FULL]);
+        assert_eq!(summary.strip_lengths, [9]);
+
+        let summary = check_mirrored::<8, 2>(&pixels, [false, false], [Brightness::FULL; 2]);
+        assert_eq!(summary.strip_lengths, [0, 0]);
+    }
+
+    #[test]
+    fn prepare_mirrored_truncates() {
+        let pixels = [[1u8, 2, 3]; 4];
+
+        let summary = check_mirrored::<3, 2>(&pixels, [true, true], [Brightness::FULL; 2]);
+        assert_eq!(summary.strip_lengths, [9, 9]);
+        assert!(summary.truncated);
+    }
+}
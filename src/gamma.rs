@@ -0,0 +1,255 @@
+use crate::pixel::Pixel;
+
+/// Fixed point representation of `1.0`, with 32 fractional bits.
+const ONE: u128 = 1 << 32;
+
+/// `ln(2)`, with 32 fractional bits.
+const LN_2: u128 = 2977044472;
+
+/// Computes `log2(x)` for a fixed point value `0 < x <= 1`.
+const fn log2(x: u128) -> i128 {
+    let mut x = x;
+    let mut result: i128 = 0;
+
+    // Normalize to [1, 2)
+    while x < ONE {
+        x <<= 1;
+        result -= ONE as i128;
+    }
+
+    // Compute the fractional bits, one by one
+    let mut bit = ONE >> 1;
+    while bit > 0 {
+        x = (x * x) >> 32;
+        if x >= 2 * ONE {
+            x >>= 1;
+            result += bit as i128;
+        }
+        bit >>= 1;
+    }
+
+    result
+}
+
+/// Computes `2^x` for a fixed point value `x <= 0`.
+const fn exp2(x: i128) -> u128 {
+    // Split into integer and fractional part
+    let shift = ((-x) as u128 + ONE - 1) >> 32;
+    let fraction = (x + (shift << 32) as i128) as u128;
+
+    // 2^f = e^(f * ln(2)), via its Taylor series
+    let z = (fraction * LN_2) >> 32;
+    let mut result = ONE;
+    let mut term = ONE;
+    let mut n = 1;
+    while term > 0 {
+        term = ((term * z) >> 32) / n;
+        result += term;
+        n += 1;
+    }
+
+    if shift >= 128 {
+        0
+    } else {
+        result >> shift
+    }
+}
+
+/// Computes `x^(numerator/denominator)` for a fixed point value `0 <= x <= 1`.
+const fn pow(x: u128, numerator: u32, denominator: u32) -> u128 {
+    if x == 0 {
+        return 0;
+    }
+    exp2(log2(x) * numerator as i128 / denominator as i128)
+}
+
+/// Converts a fixed point value `0 <= x <= 1` to `0..=255`, with rounding.
+const fn to_u8(x: u128) -> u8 {
+    let value = (x * 255 + ONE / 2) >> 32;
+    if value > 255 {
+        255
+    } else {
+        value as u8
+    }
+}
+
+/// A lookup table that maps 8-bit values to gamma corrected 8-bit values.
+///
+/// The tables are intended to be generated at compile time, so that
+/// no floating point math is required while rendering:
+///
+/// ```rust
+/// static GAMMA_2_2: GammaTable = GammaTable::power(22, 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GammaTable([u8; 256]);
+
+impl GammaTable {
+    /// Creates a table that converts gamma-encoded sRGB values to linear sRGB values.
+    pub const fn srgb() -> Self {
+        let mut table = [0; 256];
+
+        let mut i = 0;
+        while i < table.len() {
+            let value = i as u128 * ONE / 255;
+
+            // value <= 0.04045
+            let linear = if value * 100000 <= 4045 * ONE {
+                value * 100 / 1292
+            } else {
+                // ((value + 0.055) / 1.055) ^ 2.4
+                pow((value * 1000 + 55 * ONE) / 1055, 12, 5)
+            };
+
+            table[i] = to_u8(linear);
+            i += 1;
+        }
+
+        Self(table)
+    }
+
+    /// Creates a table that applies the gamma exponent `numerator / denominator`.
+    ///
+    /// For example, `GammaTable::power(22, 10)` corresponds to a gamma of 2.2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero.
+    pub const fn power(numerator: u32, denominator: u32) -> Self {
+        assert!(denominator != 0, "Gamma denominator must not be zero");
+
+        let mut table = [0; 256];
+
+        let mut i = 0;
+        while i < table.len() {
+            let value = i as u128 * ONE / 255;
+            table[i] = to_u8(pow(value, numerator, denominator));
+            i += 1;
+        }
+
+        Self(table)
+    }
+
+    /// Creates a table from precomputed values.
+    pub const fn from_table(table: [u8; 256]) -> Self {
+        Self(table)
+    }
+
+    /// Applies the gamma correction to a single value.
+    pub fn apply(&self, value: u8) -> u8 {
+        self.0[usize::from(value)]
+    }
+}
+
+/// Converts gamma-encoded sRGB values to linear sRGB values.
+///
+/// Used by [`Gamma::new()`] and the [`Pixel`] implementation of [`palette::Srgb<u8>`].
+pub static SRGB_GAMMA: GammaTable = GammaTable::srgb();
+
+/// A pixel that gets gamma corrected through a [`GammaTable`] before transmission.
+///
+/// The correction gets applied to every byte of the pixel.
+#[derive(Debug, Clone, Copy)]
+pub struct Gamma<'a, P> {
+    pixel: P,
+    table: &'a GammaTable,
+}
+
+impl<P: Pixel> Gamma<'static, P> {
+    /// Wraps a pixel to convert it from gamma-encoded sRGB to linear sRGB.
+    pub fn new(pixel: P) -> Self {
+        Self::with_table(pixel, &SRGB_GAMMA)
+    }
+}
+
+impl<'a, P: Pixel> Gamma<'a, P> {
+    /// Wraps a pixel to apply the given gamma correction.
+    pub fn with_table(pixel: P, table: &'a GammaTable) -> Self {
+        Self { pixel, table }
+    }
+}
+
+impl<P: Pixel> Pixel for Gamma<'_, P> {
+    const BYTES: usize = P::BYTES;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        self.pixel.write_bytes(out);
+        for byte in out {
+            *byte = self.table.apply(*byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_fixed(x: f64) -> u128 {
+        (x * ONE as f64).round() as u128
+    }
+
+    fn from_fixed(x: i128) -> f64 {
+        x as f64 / ONE as f64
+    }
+
+    #[test]
+    fn log2_matches_float() {
+        for &x in &[1.0, 0.75, 0.5, 0.3, 0.1, 1.0 / 255.0, 1.0 / 65536.0] {
+            let result = from_fixed(log2(to_fixed(x)));
+            assert!((result - x.log2()).abs() < 1e-6, "log2({x}) = {result}");
+        }
+    }
+
+    #[test]
+    fn exp2_matches_float() {
+        for &x in &[0.0, -0.25, -0.5, -1.0, -1.5, -7.9, -20.0] {
+            let result = from_fixed(exp2((x * ONE as f64) as i128) as i128);
+            assert!((result - x.exp2()).abs() < 1e-6, "exp2({x}) = {result}");
+        }
+        assert_eq!(exp2(-200 * ONE as i128), 0);
+    }
+
+    #[test]
+    fn pow_matches_float() {
+        for &x in &[1.0, 0.5, 0.2, 1.0 / 255.0] {
+            let result = from_fixed(pow(to_fixed(x), 22, 10) as i128);
+            assert!((result - x.powf(2.2)).abs() < 1e-6, "pow({x}) = {result}");
+        }
+        assert_eq!(pow(0, 22, 10), 0);
+    }
+
+    fn assert_table(table: &GammaTable, reference: impl Fn(f64) -> f64) {
+        assert_eq!(table.apply(0), 0);
+        assert_eq!(table.apply(255), 255);
+
+        for i in 0..=255u8 {
+            let expected = (reference(f64::from(i) / 255.0) * 255.0).round();
+            let actual = f64::from(table.apply(i));
+            assert!(
+                (actual - expected).abs() <= 1.0,
+                "{i}: {actual} != {expected}"
+            );
+
+            if i > 0 {
+                assert!(table.apply(i - 1) <= table.apply(i));
+            }
+        }
+    }
+
+    #[test]
+    fn power_table() {
+        assert_table(&GammaTable::power(22, 10), |x| x.powf(2.2));
+        assert_table(&GammaTable::power(1, 1), |x| x);
+    }
+
+    #[test]
+    fn srgb_table() {
+        assert_table(&SRGB_GAMMA, |x| {
+            if x <= 0.04045 {
+                x / 12.92
+            } else {
+                ((x + 0.055) / 1.055).powf(2.4)
+            }
+        });
+    }
+}
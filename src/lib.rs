@@ -4,18 +4,63 @@
 #![doc(issue_tracker_base_url = "https://github.com/Finomnis/ws2812-flexio/issues")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(test)]
+extern crate std;
+
+mod brightness;
+mod calibration;
+mod color_order;
+mod combinators;
+mod correction;
+#[cfg(feature = "embedded-graphics")]
+mod display;
+mod dither;
 /// Blocking driver.
 mod flexio;
+mod gamma;
+mod logical_strip;
+mod mapping;
+mod matrix;
 mod pins;
 mod pixel;
 mod pixelstream;
+mod power;
+mod rgb16;
+mod rgbcct;
+mod rgbw;
+#[cfg(feature = "smart-leds")]
+mod smart_leds;
+mod zones;
 
+#[cfg(feature = "effects")]
+#[cfg_attr(docsrs, doc(cfg(feature = "effects")))]
+pub mod effects;
 /// Possible errors that could happen.
 pub mod errors;
 
+pub use brightness::Brightness;
+pub use calibration::{Calibrated, Calibration};
+pub use color_order::{ColorOrder, Reordered};
+pub use combinators::{Chain, Offset, Pad, PixelStreamExt, Repeat, SkipPixels, TakePixels};
+pub use correction::{Corrected, CorrectionTable};
+#[cfg(feature = "embedded-graphics")]
+pub use display::MatrixDisplay;
+pub use dither::{Dither, DitherInput, Dithered};
 pub use flexio::{
-    InterruptHandler, InterruptHandlerData, PreprocessedPixels, WS2812Driver, WriteDmaResult,
+    InterruptHandler, InterruptHandlerData, PrepareSummary, PreprocessedPixels, WS2812Driver,
+    WriteDmaResult,
 };
+pub use gamma::{Gamma, GammaTable, SRGB_GAMMA};
+pub use logical_strip::{LogicalStrip, Segment};
+pub use mapping::{MapEntry, Mapped, PixelMap};
+pub use matrix::{Matrix, MatrixOrder, MatrixStrip, PanelLayout, Rotation};
 pub use pins::Pins;
-pub use pixel::Pixel;
-pub use pixelstream::IntoPixelStream;
+pub use pixel::{Pixel, PixelBytes, MAX_PIXEL_BYTES};
+pub use pixelstream::{IntoPixelStream, PixelStream, PixelStreamRef};
+pub use power::{PowerModel, PowerReport};
+pub use rgb16::Rgb16;
+pub use rgbcct::Rgbcct;
+pub use rgbw::{Rgbw, WhitePoint};
+#[cfg(feature = "smart-leds")]
+pub use smart_leds::DmaWriter;
+pub use zones::{Zone, Zones};
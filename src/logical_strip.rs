@@ -0,0 +1,255 @@
+use core::time::Duration;
+
+use imxrt_ral::{flexio, Valid};
+
+use crate::{
+    flexio::{
+        transmission_duration, transmitted_words, PrepareSummary, PreprocessedPixels, WS2812Driver,
+    },
+    mapping::{MapEntry, Mapped, PixelMap},
+    pins::Pins,
+    pixel::Pixel,
+    pixelstream::{stream_refs, PixelStream},
+};
+
+/// A part of a [`LogicalStrip`] that is connected to its own pin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// The number of pixels of the segment.
+    pub len: u16,
+    /// Whether the data pin is connected to the end of the segment.
+    pub reversed: bool,
+}
+
+impl Segment {
+    /// A segment whose data pin is connected to its start.
+    pub const fn forward(len: u16) -> Self {
+        Self {
+            len,
+            reversed: false,
+        }
+    }
+
+    /// A segment whose data pin is connected to its end.
+    pub const fn reversed(len: u16) -> Self {
+        Self {
+            len,
+            reversed: true,
+        }
+    }
+}
+
+/// A single logical LED strip that is split into multiple segments,
+/// each of which is connected to its own pin.
+///
+/// As all pins get transmitted in parallel, this multiplies the achievable
+/// framerate of long strips; see [`frame_duration()`](LogicalStrip::frame_duration).
+///
+/// ```rust
+/// // A 1200 pixel strip, split into four segments. The second and the
+/// // fourth segment are fed from their far end.
+/// const STRIP: LogicalStrip<4> = LogicalStrip::new([
+///     Segment::forward(300),
+///     Segment::reversed(300),
+///     Segment::forward(300),
+///     Segment::reversed(300),
+/// ]);
+///
+/// let framebuffer = [LinSrgb::new(0, 0, 0); 1200];
+/// STRIP.prepare(&framebuffer, &mut buffer);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalStrip<const L: usize> {
+    entries: [MapEntry; L],
+}
+
+impl<const L: usize> LogicalStrip<L> {
+    /// Creates a logical strip from its segments, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a segment is longer than [`MapEntry::MAX_LEN`] or starts
+    /// after [`MapEntry::MAX_START`].
+    pub const fn new(segments: [Segment; L]) -> Self {
+        let mut entries = [MapEntry::Blank(0); L];
+
+        let mut start: usize = 0;
+        let mut lane = 0;
+        while lane < L {
+            let Segment { len, reversed } = segments[lane];
+            assert!(
+                start <= MapEntry::MAX_START as usize,
+                "Logical strip too long"
+            );
+            entries[lane] = if reversed {
+                MapEntry::reversed(start as u16, len)
+            } else {
+                MapEntry::forward(start as u16, len)
+            };
+            start += len as usize;
+            lane += 1;
+        }
+
+        Self { entries }
+    }
+
+    /// The total number of pixels of the strip.
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(MapEntry::physical_len).sum()
+    }
+
+    /// Whether the strip doesn't contain any pixels.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates the pixel stream of a single segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - The index of the segment
+    /// * `framebuffer` - The pixels of the entire strip
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment` is not smaller than `L`.
+    pub fn segment<'a, P: Pixel>(
+        &'a self,
+        segment: usize,
+        framebuffer: &'a [P],
+    ) -> PixelStream<Option<&'a P>, Mapped<'a, 'a, P>> {
+        PixelMap::new(core::slice::from_ref(&self.entries[segment])).apply(framebuffer)
+    }
+
+    /// Creates the pixel streams of all segments.
+    pub fn streams<'a, P: Pixel>(
+        &'a self,
+        framebuffer: &'a [P],
+    ) -> [PixelStream<Option<&'a P>, Mapped<'a, 'a, P>>; L] {
+        core::array::from_fn(|segment| self.segment(segment, framebuffer))
+    }
+
+    /// Prepares a framebuffer for transmission.
+    ///
+    /// See [`PreprocessedPixels::prepare_pixels()`].
+    pub fn prepare<const N: usize, const P: usize, Px: Pixel>(
+        &self,
+        framebuffer: &[Px],
+        buffer: &mut PreprocessedPixels<N, L, P>,
+    ) -> PrepareSummary<L> {
+        let mut streams = self.streams(framebuffer);
+        buffer.prepare_pixels(stream_refs(&mut streams))
+    }
+
+    /// Writes a framebuffer to the LED strips.
+    ///
+    /// See [`WS2812Driver::write()`].
+    pub fn write<const N: u8, PINS: Pins<N, L>, Px: Pixel>(
+        &self,
+        framebuffer: &[Px],
+        driver: &mut WS2812Driver<N, L, PINS>,
+    ) where
+        flexio::Instance<N>: Valid,
+    {
+        let mut streams = self.streams(framebuffer);
+        driver.write(stream_refs(&mut streams));
+    }
+
+    /// Estimates the time it takes to transmit a frame, determined by the longest segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes_per_pixel` - The number of bytes per pixel, see [`Pixel::BYTES`]
+    pub fn frame_duration(&self, bytes_per_pixel: usize) -> Duration {
+        let longest = self
+            .entries
+            .iter()
+            .map(MapEntry::physical_len)
+            .max()
+            .unwrap_or(0);
+        transmission_duration(transmitted_words(
+            longest * bytes_per_pixel,
+            bytes_per_pixel,
+        ))
+    }
+
+    /// Estimates the time it would take to transmit a frame if the strip wasn't split.
+    ///
+    /// Compare with [`frame_duration()`](LogicalStrip::frame_duration) to see the gain.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes_per_pixel` - The number of bytes per pixel, see [`Pixel::BYTES`]
+    pub fn unsplit_frame_duration(&self, bytes_per_pixel: usize) -> Duration {
+        transmission_duration(transmitted_words(
+            self.len() * bytes_per_pixel,
+            bytes_per_pixel,
+        ))
+    }
+
+    /// Estimates the maximum achievable framerate, in frames per second.
+    ///
+    /// This is only realistic for DMA based writes; see the
+    /// [crate level documentation](crate#framerate).
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes_per_pixel` - The number of bytes per pixel, see [`Pixel::BYTES`]
+    pub fn frame_rate(&self, bytes_per_pixel: usize) -> u32 {
+        let micros = self.frame_duration(bytes_per_pixel).as_micros();
+        (1_000_000 / micros) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::pixelstream::PixelStreamRef;
+
+    const STRIP: LogicalStrip<2> = LogicalStrip::new([Segment::forward(2), Segment::reversed(3)]);
+
+    #[test]
+    fn segments_split_the_framebuffer() {
+        let framebuffer = [1u32, 2, 3, 4, 5];
+
+        let [mut first, mut second] = STRIP.streams(&framebuffer);
+        let first: Vec<_> = core::iter::from_fn(|| first.next()).collect();
+        let second: Vec<_> = core::iter::from_fn(|| second.next()).collect();
+
+        assert_eq!(first, [0, 0, 1, 0, 0, 2]);
+        assert_eq!(second, [0, 0, 5, 0, 0, 4, 0, 0, 3]);
+
+        let mut buffer = PreprocessedPixels::<8, 2>::new();
+        let summary = STRIP.prepare(&framebuffer, &mut buffer);
+        assert_eq!(summary.strip_lengths, [6, 9]);
+        assert!(STRIP.frame_duration(3) < STRIP.unsplit_frame_duration(3));
+    }
+
+    #[test]
+    fn frame_duration_matches_buffer() {
+        let framebuffer = [[1u8, 2, 3]; 5];
+
+        let mut buffer = PreprocessedPixels::<16, 2>::new();
+        STRIP.prepare(&framebuffer, &mut buffer);
+        assert_eq!(STRIP.frame_duration(3), buffer.frame_duration());
+
+        let unsplit = LogicalStrip::new([Segment::forward(5)]);
+        let mut buffer = PreprocessedPixels::<16, 1>::new();
+        unsplit.prepare(&framebuffer, &mut buffer);
+        assert_eq!(STRIP.unsplit_frame_duration(3), buffer.frame_duration());
+    }
+
+    #[test]
+    #[should_panic = "Logical strip too long"]
+    fn too_long() {
+        LogicalStrip::new([Segment::forward(MapEntry::MAX_LEN); 4]);
+    }
+
+    #[test]
+    #[should_panic = "Section length out of range"]
+    fn segment_too_long() {
+        LogicalStrip::new([Segment::forward(MapEntry::MAX_LEN + 1)]);
+    }
+}
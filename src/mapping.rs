@@ -0,0 +1,401 @@
+use crate::{
+    pixel::Pixel,
+    pixelstream::{IntoPixelStream, PixelStream},
+};
+
+/// A single entry of a [`PixelMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapEntry {
+    /// A section of physical LEDs that shows a range of logical pixels.
+    Range {
+        /// The first logical pixel of the section.
+        start: u16,
+        /// The number of pixels of the section.
+        len: u16,
+        /// Whether the section is mounted backwards.
+        reversed: bool,
+    },
+    /// A number of physical LEDs that don't show any logical pixel and stay black,
+    /// like sacrificial pixels that amplify the signal across long cables.
+    Blank(u16),
+}
+
+impl MapEntry {
+    /// The size of a serialized entry, in bytes.
+    pub const SERIALIZED_SIZE: usize = 4;
+
+    /// The maximum length of a section, limited by the serialization format.
+    pub const MAX_LEN: u16 = 0x7FFF;
+
+    /// The maximum first logical pixel of a section, limited by the serialization format.
+    pub const MAX_START: u16 = u16::MAX - 1;
+
+    /// The marker in the `start` field that identifies a blank entry.
+    const BLANK_MARKER: u16 = u16::MAX;
+    /// The bit in the `len` field that marks a reversed section.
+    const REVERSED_BIT: u16 = 0x8000;
+
+    /// Creates a section that shows the given logical pixels in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is larger than [`MAX_START`](MapEntry::MAX_START)
+    /// or `len` is larger than [`MAX_LEN`](MapEntry::MAX_LEN).
+    pub const fn forward(start: u16, len: u16) -> Self {
+        assert!(start <= Self::MAX_START, "Section start out of range");
+        assert!(len <= Self::MAX_LEN, "Section length out of range");
+        Self::Range {
+            start,
+            len,
+            reversed: false,
+        }
+    }
+
+    /// Creates a section that shows the given logical pixels in reverse order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is larger than [`MAX_START`](MapEntry::MAX_START)
+    /// or `len` is larger than [`MAX_LEN`](MapEntry::MAX_LEN).
+    pub const fn reversed(start: u16, len: u16) -> Self {
+        assert!(start <= Self::MAX_START, "Section start out of range");
+        assert!(len <= Self::MAX_LEN, "Section length out of range");
+        Self::Range {
+            start,
+            len,
+            reversed: true,
+        }
+    }
+
+    /// The number of physical LEDs of this entry.
+    pub const fn physical_len(&self) -> usize {
+        match *self {
+            Self::Range { len, .. } => len as usize,
+            Self::Blank(len) => len as usize,
+        }
+    }
+
+    /// Serializes the entry, for example to store it in flash.
+    ///
+    /// Returns `None` if the entry can't be represented by the serialization format,
+    /// which is the case for lengths above [`MAX_LEN`](MapEntry::MAX_LEN) and
+    /// starts above [`MAX_START`](MapEntry::MAX_START). Entries created through
+    /// [`forward()`](MapEntry::forward) and [`reversed()`](MapEntry::reversed)
+    /// can always be serialized.
+    pub const fn to_le_bytes(&self) -> Option<[u8; Self::SERIALIZED_SIZE]> {
+        let (start, len) = match *self {
+            Self::Range {
+                start,
+                len,
+                reversed,
+            } => {
+                if start > Self::MAX_START || len > Self::MAX_LEN {
+                    return None;
+                }
+                (
+                    start,
+                    if reversed {
+                        len | Self::REVERSED_BIT
+                    } else {
+                        len
+                    },
+                )
+            }
+            Self::Blank(len) => {
+                if len > Self::MAX_LEN {
+                    return None;
+                }
+                (Self::BLANK_MARKER, len)
+            }
+        };
+
+        let start = start.to_le_bytes();
+        let len = len.to_le_bytes();
+        Some([start[0], start[1], len[0], len[1]])
+    }
+
+    /// Deserializes an entry that was serialized with [`to_le_bytes()`](MapEntry::to_le_bytes).
+    pub const fn from_le_bytes(bytes: [u8; Self::SERIALIZED_SIZE]) -> Self {
+        let start = u16::from_le_bytes([bytes[0], bytes[1]]);
+        let len = u16::from_le_bytes([bytes[2], bytes[3]]);
+
+        if start == Self::BLANK_MARKER {
+            Self::Blank(len & Self::MAX_LEN)
+        } else {
+            Self::Range {
+                start,
+                len: len & Self::MAX_LEN,
+                reversed: len & Self::REVERSED_BIT != 0,
+            }
+        }
+    }
+}
+
+/// A mapping from a logical frame buffer to the physical LEDs of a strip.
+///
+/// The map is a list of [`MapEntry`]s, each of which describes a section of
+/// consecutive physical LEDs. Logical pixels that aren't part of any section
+/// aren't displayed; this is used for LEDs that were cut out and bridged.
+///
+/// As the map is plain data, it can be loaded at runtime, so layouts can
+/// be patched without touching the rendering code:
+///
+/// ```rust
+/// // LED 17 was cut out, the strip starts with a sacrificial pixel
+/// // and the second half is mounted backwards
+/// const MAP: PixelMap = PixelMap::new(&[
+///     MapEntry::Blank(1),
+///     MapEntry::forward(0, 17),
+///     MapEntry::forward(18, 32),
+///     MapEntry::reversed(50, 50),
+/// ]);
+///
+/// buffer.prepare_pixels([&mut MAP.apply(&framebuffer)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelMap<'a> {
+    entries: &'a [MapEntry],
+}
+
+impl<'a> PixelMap<'a> {
+    /// Creates a pixel map from a list of entries.
+    pub const fn new(entries: &'a [MapEntry]) -> Self {
+        Self { entries }
+    }
+
+    /// The entries of the map.
+    pub const fn entries(&self) -> &'a [MapEntry] {
+        self.entries
+    }
+
+    /// The number of physical LEDs of the strip.
+    pub fn physical_len(&self) -> usize {
+        self.entries.iter().map(MapEntry::physical_len).sum()
+    }
+
+    /// Finds the physical LED that shows the given logical pixel.
+    ///
+    /// Returns `None` if the logical pixel isn't displayed.
+    pub fn physical_index(&self, logical: usize) -> Option<usize> {
+        let mut offset = 0;
+        for entry in self.entries {
+            if let MapEntry::Range {
+                start,
+                len,
+                reversed,
+            } = *entry
+            {
+                let start = usize::from(start);
+                let len = usize::from(len);
+                if (start..start + len).contains(&logical) {
+                    let position = logical - start;
+                    return Some(if reversed {
+                        offset + len - 1 - position
+                    } else {
+                        offset + position
+                    });
+                }
+            }
+            offset += entry.physical_len();
+        }
+        None
+    }
+
+    /// Applies the map to a logical frame buffer.
+    ///
+    /// Logical pixels that are referenced by the map but don't exist
+    /// in the frame buffer are black.
+    pub fn apply<'b, P: Pixel>(
+        &self,
+        frame: &'b [P],
+    ) -> PixelStream<Option<&'b P>, Mapped<'a, 'b, P>> {
+        self.pixels(frame).into_pixel_stream()
+    }
+
+    /// Iterates over the pixels of a logical frame buffer, in the order of the physical LEDs.
+    pub fn pixels<'b, P>(&self, frame: &'b [P]) -> Mapped<'a, 'b, P> {
+        Mapped {
+            entries: self.entries,
+            frame,
+            next_index: 0,
+            remaining: 0,
+            step: Step::Blank,
+        }
+    }
+}
+
+/// The direction in which the current section of a [`Mapped`] iterator is walked.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Forward,
+    Backward,
+    Blank,
+}
+
+/// The pixels of a mapped frame buffer, in the order of the physical LEDs.
+///
+/// Yields `None` for LEDs that don't show a pixel of the frame buffer.
+/// Created by [`PixelMap::pixels()`]; [`PixelMap::apply()`] wraps it in a pixel stream.
+#[derive(Debug, Clone)]
+pub struct Mapped<'a, 'b, P> {
+    entries: &'a [MapEntry],
+    frame: &'b [P],
+    next_index: usize,
+    remaining: usize,
+    step: Step,
+}
+
+impl<'b, P> Iterator for Mapped<'_, 'b, P> {
+    type Item = Option<&'b P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining == 0 {
+            let (entry, entries) = self.entries.split_first()?;
+            self.entries = entries;
+
+            self.remaining = entry.physical_len();
+            (self.next_index, self.step) = match *entry {
+                MapEntry::Range {
+                    start,
+                    len,
+                    reversed: false,
+                } if len != 0 => (usize::from(start), Step::Forward),
+                MapEntry::Range {
+                    start,
+                    len,
+                    reversed: true,
+                } if len != 0 => (usize::from(start) + usize::from(len) - 1, Step::Backward),
+                _ => (0, Step::Blank),
+            };
+        }
+        self.remaining -= 1;
+
+        Some(match self.step {
+            Step::Forward => {
+                let pixel = self.frame.get(self.next_index);
+                self.next_index += 1;
+                pixel
+            }
+            Step::Backward => {
+                let pixel = self.frame.get(self.next_index);
+                self.next_index = self.next_index.wrapping_sub(1);
+                pixel
+            }
+            Step::Blank => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::pixelstream::PixelStreamRef;
+
+    #[test]
+    fn serialization_round_trip() {
+        for entry in [
+            MapEntry::forward(0, 0),
+            MapEntry::forward(17, 300),
+            MapEntry::reversed(50, 50),
+            MapEntry::forward(MapEntry::MAX_START, MapEntry::MAX_LEN),
+            MapEntry::reversed(MapEntry::MAX_START, MapEntry::MAX_LEN),
+            MapEntry::Blank(0),
+            MapEntry::Blank(MapEntry::MAX_LEN),
+        ] {
+            assert_eq!(MapEntry::from_le_bytes(entry.to_le_bytes().unwrap()), entry);
+        }
+    }
+
+    #[test]
+    fn unrepresentable_entries_dont_serialize() {
+        let long = MapEntry::Range {
+            start: 3,
+            len: MapEntry::MAX_LEN + 1,
+            reversed: true,
+        };
+        let blank_start = MapEntry::Range {
+            start: u16::MAX,
+            len: 1,
+            reversed: false,
+        };
+
+        assert_eq!(long.to_le_bytes(), None);
+        assert_eq!(blank_start.to_le_bytes(), None);
+        assert_eq!(MapEntry::Blank(MapEntry::MAX_LEN + 1).to_le_bytes(), None);
+    }
+
+    #[test]
+    #[should_panic = "Section length out of range"]
+    fn len_out_of_range() {
+        MapEntry::reversed(0, MapEntry::MAX_LEN + 1);
+    }
+
+    #[test]
+    #[should_panic = "Section start out of range"]
+    fn start_out_of_range() {
+        MapEntry::forward(u16::MAX, 1);
+    }
+
+    const MAP: PixelMap = PixelMap::new(&[
+        MapEntry::Blank(1),
+        MapEntry::forward(0, 2),
+        MapEntry::forward(3, 2),
+        MapEntry::reversed(5, 3),
+    ]);
+
+    #[test]
+    fn physical_index() {
+        assert_eq!(MAP.physical_len(), 8);
+
+        let physical: Vec<_> = (0..9).map(|logical| MAP.physical_index(logical)).collect();
+        assert_eq!(
+            physical,
+            [
+                Some(1),
+                Some(2),
+                None,
+                Some(3),
+                Some(4),
+                Some(7),
+                Some(6),
+                Some(5),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn mapped_pixels() {
+        let frame = [10u32, 11, 12, 13, 14, 15, 16];
+
+        let pixels: Vec<_> = MAP.pixels(&frame).collect();
+        assert_eq!(
+            pixels,
+            [
+                None,
+                Some(&10),
+                Some(&11),
+                Some(&13),
+                Some(&14),
+                None,
+                Some(&16),
+                Some(&15)
+            ]
+        );
+
+        for (physical, pixel) in MAP.pixels(&frame).enumerate() {
+            if let Some(pixel) = pixel {
+                let logical = frame.iter().position(|p| p == pixel);
+                assert_eq!(MAP.physical_index(logical.unwrap()), Some(physical));
+            }
+        }
+
+        let mut stream = MAP.apply(&frame);
+        assert_eq!(stream.bytes_per_pixel(), 3);
+        let bytes: Vec<_> = core::iter::from_fn(|| stream.next()).collect();
+        assert_eq!(bytes.len(), 8 * 3);
+        assert_eq!(bytes[..6], [0, 0, 0, 0, 0, 10]);
+    }
+}
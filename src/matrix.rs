@@ -0,0 +1,536 @@
+use crate::{
+    flexio::{PrepareSummary, PreprocessedPixels},
+    pixel::Pixel,
+    pixelstream::{stream_refs, IntoPixelStream, PixelStream},
+};
+
+/// The direction in which the LEDs of a matrix panel are wired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MatrixOrder {
+    /// The LEDs are wired row by row.
+    #[default]
+    RowMajor,
+    /// The LEDs are wired column by column.
+    ColumnMajor,
+}
+
+/// The clockwise rotation with which a matrix panel is mounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    /// Mounted as wired.
+    #[default]
+    Deg0,
+    /// Rotated by 90 degrees.
+    Deg90,
+    /// Rotated by 180 degrees.
+    Deg180,
+    /// Rotated by 270 degrees.
+    Deg270,
+}
+
+/// The wiring of a single panel, for matrices whose panels are not all mounted the same way.
+///
+/// See [`Matrix::panel_layouts()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PanelLayout {
+    /// The rotation with which the panel is mounted.
+    pub rotation: Rotation,
+    /// Whether every other row (or column) of the panel is wired backwards.
+    pub serpentine: bool,
+}
+
+/// The layout of a 2D LED matrix that spans one or more LED strips.
+///
+/// The matrix consists of a grid of identical panels. The panels are
+/// chained in row-major order, and each LED strip drives a fixed number
+/// of consecutive panels; this way, a wall can be split into multiple
+/// parts that get transmitted in parallel.
+///
+/// A single panel is described by its visible size, the order in which its
+/// LEDs are wired, and the rotation with which it is mounted. Rotation and
+/// serpentine wiring can be overridden per panel via
+/// [`panel_layouts()`](Matrix::panel_layouts).
+///
+/// ```rust
+/// // A 30x30 wall, made of three 30x10 serpentine panels,
+/// // each connected to its own output.
+/// const MATRIX: Matrix = Matrix::new(30, 10).serpentine(true).tiles(1, 3);
+///
+/// let mut framebuffer = [LinSrgb::new(0, 0, 0); 30 * 30];
+/// framebuffer[MATRIX.width() * y + x] = LinSrgb::new(255, 0, 0);
+///
+/// MATRIX.render(&framebuffer, &mut buffer);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Matrix {
+    panel_width: usize,
+    panel_height: usize,
+    order: MatrixOrder,
+    serpentine: bool,
+    rotation: Rotation,
+    tiles_x: usize,
+    tiles_y: usize,
+    panels_per_strip: usize,
+    panel_layouts: &'static [PanelLayout],
+}
+
+impl Matrix {
+    /// Creates a matrix that consists of a single panel of the given size.
+    ///
+    /// The panel is wired progressively in row-major order and is not rotated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel width or height is zero.
+    pub const fn new(panel_width: usize, panel_height: usize) -> Self {
+        assert!(
+            panel_width > 0 && panel_height > 0,
+            "Panel size must not be zero"
+        );
+        Self {
+            panel_width,
+            panel_height,
+            order: MatrixOrder::RowMajor,
+            serpentine: false,
+            rotation: Rotation::Deg0,
+            tiles_x: 1,
+            tiles_y: 1,
+            panels_per_strip: 1,
+            panel_layouts: &[],
+        }
+    }
+
+    /// Sets the order in which the LEDs of each panel are wired.
+    pub const fn order(self, order: MatrixOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Sets whether every other row (or column) of each panel is wired backwards.
+    pub const fn serpentine(self, serpentine: bool) -> Self {
+        Self { serpentine, ..self }
+    }
+
+    /// Sets the rotation with which each panel is mounted.
+    ///
+    /// The panel size always refers to the visible size, so panels rotated by
+    /// 90 or 270 degrees are wired along their visible columns.
+    pub const fn rotation(self, rotation: Rotation) -> Self {
+        Self { rotation, ..self }
+    }
+
+    /// Overrides the rotation and serpentine wiring of individual panels.
+    ///
+    /// The layouts are indexed by panel, in the order in which the panels are
+    /// chained. Panels without an entry use the layout set via
+    /// [`rotation()`](Matrix::rotation) and [`serpentine()`](Matrix::serpentine).
+    ///
+    /// ```rust
+    /// // Two panels on top of each other; the upper one is mounted upside down.
+    /// const MATRIX: Matrix = Matrix::new(16, 16).tiles(1, 2).panel_layouts(&[PanelLayout {
+    ///     rotation: Rotation::Deg180,
+    ///     serpentine: true,
+    /// }]);
+    /// ```
+    pub const fn panel_layouts(self, panel_layouts: &'static [PanelLayout]) -> Self {
+        Self {
+            panel_layouts,
+            ..self
+        }
+    }
+
+    /// Arranges multiple panels in a grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `tiles_x` - The number of panels next to each other
+    /// * `tiles_y` - The number of panels on top of each other
+    pub const fn tiles(self, tiles_x: usize, tiles_y: usize) -> Self {
+        Self {
+            tiles_x,
+            tiles_y,
+            ..self
+        }
+    }
+
+    /// Sets the number of consecutive panels that are chained on each LED strip.
+    ///
+    /// Defaults to one panel per strip.
+    pub const fn panels_per_strip(self, panels_per_strip: usize) -> Self {
+        Self {
+            panels_per_strip,
+            ..self
+        }
+    }
+
+    /// The total width of the matrix, in pixels.
+    pub const fn width(&self) -> usize {
+        self.panel_width * self.tiles_x
+    }
+
+    /// The total height of the matrix, in pixels.
+    pub const fn height(&self) -> usize {
+        self.panel_height * self.tiles_y
+    }
+
+    /// The number of LED strips the matrix spans.
+    pub const fn num_strips(&self) -> usize {
+        let panels = self.tiles_x * self.tiles_y;
+        if self.panels_per_strip == 0 {
+            0
+        } else {
+            panels.div_ceil(self.panels_per_strip)
+        }
+    }
+
+    /// The number of pixels on the given LED strip.
+    pub const fn strip_len(&self, strip: usize) -> usize {
+        let panels = self.tiles_x * self.tiles_y;
+        let first_panel = strip * self.panels_per_strip;
+        if first_panel >= panels {
+            return 0;
+        }
+
+        let strip_panels = panels - first_panel;
+        let strip_panels = if strip_panels < self.panels_per_strip {
+            strip_panels
+        } else {
+            self.panels_per_strip
+        };
+        strip_panels * self.panel_size()
+    }
+
+    /// The number of pixels of a single panel.
+    const fn panel_size(&self) -> usize {
+        self.panel_width * self.panel_height
+    }
+
+    /// The layout of the given panel.
+    const fn panel_layout(&self, tile: usize) -> PanelLayout {
+        if tile < self.panel_layouts.len() {
+            self.panel_layouts[tile]
+        } else {
+            PanelLayout {
+                rotation: self.rotation,
+                serpentine: self.serpentine,
+            }
+        }
+    }
+
+    /// The width of a panel in the direction of its wiring.
+    const fn wired_width(&self, rotation: Rotation) -> usize {
+        match rotation {
+            Rotation::Deg0 | Rotation::Deg180 => self.panel_width,
+            Rotation::Deg90 | Rotation::Deg270 => self.panel_height,
+        }
+    }
+
+    /// The height of a panel in the direction of its wiring.
+    const fn wired_height(&self, rotation: Rotation) -> usize {
+        match rotation {
+            Rotation::Deg0 | Rotation::Deg180 => self.panel_height,
+            Rotation::Deg90 | Rotation::Deg270 => self.panel_width,
+        }
+    }
+
+    /// Finds the LED that displays the given pixel.
+    ///
+    /// Returns the index of the LED strip and the position on the strip,
+    /// or `None` if the pixel is outside of the matrix.
+    pub const fn physical_position(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if x >= self.width() || y >= self.height() || self.panels_per_strip == 0 {
+            return None;
+        }
+
+        let tile = (y / self.panel_height) * self.tiles_x + x / self.panel_width;
+        let (x, y) = (x % self.panel_width, y % self.panel_height);
+        let (width, height) = (self.panel_width, self.panel_height);
+        let layout = self.panel_layout(tile);
+
+        let (wired_x, wired_y) = match layout.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (y, width - 1 - x),
+            Rotation::Deg180 => (width - 1 - x, height - 1 - y),
+            Rotation::Deg270 => (height - 1 - y, x),
+        };
+
+        let (line, line_len, position) = match self.order {
+            MatrixOrder::RowMajor => (wired_y, self.wired_width(layout.rotation), wired_x),
+            MatrixOrder::ColumnMajor => (wired_x, self.wired_height(layout.rotation), wired_y),
+        };
+        let position = if layout.serpentine && line % 2 == 1 {
+            line_len - 1 - position
+        } else {
+            position
+        };
+
+        let strip = tile / self.panels_per_strip;
+        let panel = tile % self.panels_per_strip;
+        Some((
+            strip,
+            panel * self.panel_size() + line * line_len + position,
+        ))
+    }
+
+    /// Finds the pixel that gets displayed by the given LED.
+    ///
+    /// This is the inverse of [`physical_position()`](Matrix::physical_position).
+    /// Returns `None` if the LED is not part of the matrix.
+    pub const fn logical_position(&self, strip: usize, index: usize) -> Option<(usize, usize)> {
+        if index >= self.strip_len(strip) {
+            return None;
+        }
+
+        let tile = strip * self.panels_per_strip + index / self.panel_size();
+        let index = index % self.panel_size();
+        let layout = self.panel_layout(tile);
+
+        let line_len = match self.order {
+            MatrixOrder::RowMajor => self.wired_width(layout.rotation),
+            MatrixOrder::ColumnMajor => self.wired_height(layout.rotation),
+        };
+
+        let line = index / line_len;
+        let position = index % line_len;
+        let position = if layout.serpentine && line % 2 == 1 {
+            line_len - 1 - position
+        } else {
+            position
+        };
+
+        let (wired_x, wired_y) = match self.order {
+            MatrixOrder::RowMajor => (position, line),
+            MatrixOrder::ColumnMajor => (line, position),
+        };
+
+        let (width, height) = (self.panel_width, self.panel_height);
+        let (x, y) = match layout.rotation {
+            Rotation::Deg0 => (wired_x, wired_y),
+            Rotation::Deg90 => (width - 1 - wired_y, wired_x),
+            Rotation::Deg180 => (width - 1 - wired_x, height - 1 - wired_y),
+            Rotation::Deg270 => (wired_y, height - 1 - wired_x),
+        };
+
+        Some((
+            (tile % self.tiles_x) * width + x,
+            (tile / self.tiles_x) * height + y,
+        ))
+    }
+
+    /// Creates the pixel stream of a single LED strip.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The index of the LED strip
+    /// * `framebuffer` - The pixels of the matrix, row by row.
+    ///   Pixels that are missing in the framebuffer are black.
+    pub fn strip<'a, P: Pixel>(
+        &'a self,
+        strip: usize,
+        framebuffer: &'a [P],
+    ) -> PixelStream<Option<&'a P>, MatrixStrip<'a, P>> {
+        self.strip_pixels(strip, framebuffer).into_pixel_stream()
+    }
+
+    /// Iterates over the pixels of a single LED strip, in the order of its LEDs.
+    ///
+    /// Yields `None` for pixels that are missing in the framebuffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The index of the LED strip
+    /// * `framebuffer` - The pixels of the matrix, row by row.
+    pub fn strip_pixels<'a, P>(&'a self, strip: usize, framebuffer: &'a [P]) -> MatrixStrip<'a, P> {
+        MatrixStrip {
+            matrix: self,
+            framebuffer,
+            strip,
+            index: 0,
+        }
+    }
+
+    /// Renders a framebuffer into a preprocessed pixel buffer.
+    ///
+    /// LED strips that are not part of the matrix stay dark.
+    ///
+    /// # Arguments
+    ///
+    /// * `framebuffer` - The pixels of the matrix, row by row.
+    ///   Pixels that are missing in the framebuffer are black.
+    /// * `buffer` - The buffer to render into
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix spans more LED strips than the buffer has lanes.
+    pub fn render<const N: usize, const L: usize, const P: usize, Px: Pixel>(
+        &self,
+        framebuffer: &[Px],
+        buffer: &mut PreprocessedPixels<N, L, P>,
+    ) -> PrepareSummary<L> {
+        assert!(
+            self.num_strips() <= L,
+            "Matrix spans more strips than the buffer has lanes"
+        );
+        let mut strips: [_; L] = core::array::from_fn(|strip| self.strip(strip, framebuffer));
+        buffer.prepare_pixels(stream_refs(&mut strips))
+    }
+}
+
+/// The pixels of a single LED strip of a matrix, created by [`Matrix::strip_pixels()`].
+#[derive(Debug, Clone)]
+pub struct MatrixStrip<'a, P> {
+    matrix: &'a Matrix,
+    framebuffer: &'a [P],
+    strip: usize,
+    index: usize,
+}
+
+impl<'a, P> Iterator for MatrixStrip<'a, P> {
+    type Item = Option<&'a P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, y) = self.matrix.logical_position(self.strip, self.index)?;
+        self.index += 1;
+
+        Some(self.framebuffer.get(y * self.matrix.width() + x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    const MIXED_LAYOUTS: &[PanelLayout] = &[
+        PanelLayout {
+            rotation: Rotation::Deg90,
+            serpentine: true,
+        },
+        PanelLayout {
+            rotation: Rotation::Deg0,
+            serpentine: false,
+        },
+        PanelLayout {
+            rotation: Rotation::Deg180,
+            serpentine: true,
+        },
+    ];
+
+    fn matrices() -> impl Iterator<Item = Matrix> {
+        let rotations = [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ];
+        let orders = [MatrixOrder::RowMajor, MatrixOrder::ColumnMajor];
+
+        rotations.into_iter().flat_map(move |rotation| {
+            orders.into_iter().flat_map(move |order| {
+                [false, true].into_iter().flat_map(move |serpentine| {
+                    [(1, 1, 1), (3, 2, 2), (2, 3, 4)].into_iter().flat_map(
+                        move |(tiles_x, tiles_y, panels_per_strip)| {
+                            let matrix = Matrix::new(5, 3)
+                                .rotation(rotation)
+                                .order(order)
+                                .serpentine(serpentine)
+                                .tiles(tiles_x, tiles_y)
+                                .panels_per_strip(panels_per_strip);
+                            [matrix, matrix.panel_layouts(MIXED_LAYOUTS)]
+                        },
+                    )
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn physical_position_is_inverse_of_logical_position() {
+        for matrix in matrices() {
+            let mut leds = Vec::new();
+            for y in 0..matrix.height() {
+                for x in 0..matrix.width() {
+                    let (strip, index) = matrix.physical_position(x, y).unwrap();
+                    assert!(index < matrix.strip_len(strip), "{matrix:?}");
+                    assert_eq!(matrix.logical_position(strip, index), Some((x, y)));
+                    leds.push((strip, index));
+                }
+            }
+
+            // Every LED displays exactly one pixel
+            let num_leds: usize = (0..matrix.num_strips())
+                .map(|strip| matrix.strip_len(strip))
+                .sum();
+            leds.sort_unstable();
+            leds.dedup();
+            assert_eq!(leds.len(), num_leds, "{matrix:?}");
+
+            assert_eq!(matrix.physical_position(matrix.width(), 0), None);
+            assert_eq!(matrix.physical_position(0, matrix.height()), None);
+            assert_eq!(matrix.logical_position(matrix.num_strips(), 0), None);
+        }
+    }
+
+    #[test]
+    fn serpentine_wiring() {
+        let matrix = Matrix::new(3, 2).serpentine(true);
+        let framebuffer = [0u32, 1, 2, 3, 4, 5];
+
+        let pixels: Vec<_> = matrix.strip_pixels(0, &framebuffer).flatten().collect();
+        assert_eq!(pixels, [&0, &1, &2, &5, &4, &3]);
+    }
+
+    #[test]
+    fn per_panel_layouts() {
+        const LAYOUTS: &[PanelLayout] = &[
+            PanelLayout {
+                rotation: Rotation::Deg0,
+                serpentine: false,
+            },
+            PanelLayout {
+                rotation: Rotation::Deg180,
+                serpentine: true,
+            },
+        ];
+        let matrix = Matrix::new(2, 2).tiles(2, 1).panel_layouts(LAYOUTS);
+        #[rustfmt::skip]
+        let framebuffer = [
+            0u32, 1, 2, 3,
+            4,    5, 6, 7,
+        ];
+
+        let pixels: Vec<_> = matrix.strip_pixels(0, &framebuffer).flatten().collect();
+        assert_eq!(pixels, [&0, &1, &4, &5]);
+        let pixels: Vec<_> = matrix.strip_pixels(1, &framebuffer).flatten().collect();
+        assert_eq!(pixels, [&7, &6, &2, &3]);
+    }
+
+    #[test]
+    #[should_panic = "Panel size must not be zero"]
+    fn empty_panel() {
+        Matrix::new(4, 0);
+    }
+
+    #[test]
+    #[should_panic = "Matrix spans more strips than the buffer has lanes"]
+    fn render_too_many_strips() {
+        let matrix = Matrix::new(2, 1).tiles(1, 3);
+        let mut buffer = PreprocessedPixels::<8, 2>::new();
+        matrix.render(&[0u32; 6], &mut buffer);
+    }
+
+    #[test]
+    fn render_splits_strips() {
+        let matrix = Matrix::new(2, 1).tiles(1, 3).panels_per_strip(2);
+        let framebuffer = [1u32, 2, 3, 4, 5];
+
+        let mut buffer = PreprocessedPixels::<8, 3>::new();
+        let summary = matrix.render(&framebuffer, &mut buffer);
+
+        assert_eq!(summary.strip_lengths, [12, 6, 0]);
+        assert!(!summary.truncated);
+
+        let last: Vec<_> = matrix.strip_pixels(1, &framebuffer).collect();
+        assert_eq!(last, [Some(&5), None]);
+    }
+}
@@ -1,30 +1,116 @@
+use core::marker::PhantomData;
+
+/// The maximum number of bytes a single [`Pixel`] can consist of.
+pub const MAX_PIXEL_BYTES: usize = 8;
+
 /// A pixel that can be rendered with this library.
 pub trait Pixel {
-    /// The return type of the [into_ws2812_bytes()](Pixel::into_ws2812_bytes) function.
-    type BytesIter: Iterator<Item = u8>;
+    /// The number of bytes this pixel occupies on the LED strip.
+    ///
+    /// Must not be larger than [`MAX_PIXEL_BYTES`]; this is checked at compile time.
+    ///
+    /// Can be used to size a [`PreprocessedPixels`](crate::PreprocessedPixels) buffer
+    /// for a specific pixel type, like
+    /// `PreprocessedPixels<N, L, { <MyPixel as Pixel>::BYTES }>`.
+    const BYTES: usize;
 
-    /// Return the raw bytes that should be sent to the LED strip.
+    /// Writes the raw bytes that should be sent to the LED strip.
+    ///
+    /// The length of `out` is always [`BYTES`](Pixel::BYTES).
     ///
     /// IMPORTANT: Be aware that WS2812 strips are GRB encoded.
-    fn into_ws2812_bytes(self) -> Self::BytesIter;
+    fn write_bytes(&self, out: &mut [u8]);
+
+    /// Return the raw bytes that should be sent to the LED strip.
+    ///
+    /// This is an iterator based adapter for [`write_bytes()`](Pixel::write_bytes).
+    fn into_ws2812_bytes(self) -> PixelBytes
+    where
+        Self: Sized,
+    {
+        PixelBytes::new(&self)
+    }
 }
 
+/// Compile time checks of the size of a [`Pixel`].
+pub(crate) struct PixelSize<P>(PhantomData<P>);
+
+impl<P: Pixel> PixelSize<P> {
+    /// Fails to compile if [`Pixel::BYTES`] exceeds [`MAX_PIXEL_BYTES`].
+    ///
+    /// Only gets evaluated if it is referenced from code that gets instantiated.
+    pub(crate) const CHECK: () = assert!(
+        P::BYTES <= MAX_PIXEL_BYTES,
+        "Pixel::BYTES must not be larger than MAX_PIXEL_BYTES"
+    );
+}
+
+/// The raw bytes of a [`Pixel`].
+///
+/// Returned by [`Pixel::into_ws2812_bytes()`].
+#[derive(Debug, Clone)]
+pub struct PixelBytes {
+    bytes: [u8; MAX_PIXEL_BYTES],
+    pos: usize,
+    len: usize,
+}
+
+impl PixelBytes {
+    fn new<P: Pixel>(pixel: &P) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = PixelSize::<P>::CHECK;
+
+        let mut bytes = [0; MAX_PIXEL_BYTES];
+        pixel.write_bytes(&mut bytes[..P::BYTES]);
+
+        Self {
+            bytes,
+            pos: 0,
+            len: P::BYTES,
+        }
+    }
+}
+
+impl Iterator for PixelBytes {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos < self.len {
+            let byte = self.bytes[self.pos];
+            self.pos += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PixelBytes {}
+
 /// Raw RGB data.
 impl Pixel for [u8; 3] {
-    type BytesIter = core::array::IntoIter<u8, 3>;
+    const BYTES: usize = 3;
 
-    fn into_ws2812_bytes(self) -> Self::BytesIter {
+    fn write_bytes(&self, out: &mut [u8]) {
         // Neopixel strips want GRB data
-        [self[1], self[0], self[2]].into_iter()
+        out.copy_from_slice(&[self[1], self[0], self[2]]);
     }
 }
 
 /// Raw RGBW data.
+///
+/// The bytes get transmitted unchanged, so they have to be in the order
+/// the strip expects; usually GRBW.
 impl Pixel for [u8; 4] {
-    type BytesIter = core::array::IntoIter<u8, 4>;
+    const BYTES: usize = 4;
 
-    fn into_ws2812_bytes(self) -> Self::BytesIter {
-        self.into_iter()
+    fn write_bytes(&self, out: &mut [u8]) {
+        out.copy_from_slice(self);
     }
 }
 
@@ -37,19 +123,209 @@ impl Pixel for [u8; 4] {
 /// More info can be found in the documentation of the
 /// [palette] crate.
 impl Pixel for palette::LinSrgb<u8> {
-    type BytesIter = core::array::IntoIter<u8, 3>;
+    const BYTES: usize = 3;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        out.copy_from_slice(&[self.green, self.red, self.blue]);
+    }
+}
+
+/// 8-bit gamma-encoded sRGB.
+///
+/// Gets converted to linear sRGB through the [`SRGB_GAMMA`](crate::SRGB_GAMMA)
+/// lookup table, so no floating point math is involved.
+impl Pixel for palette::Srgb<u8> {
+    const BYTES: usize = 3;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        let gamma = &crate::gamma::SRGB_GAMMA;
+        out.copy_from_slice(&[
+            gamma.apply(self.green),
+            gamma.apply(self.red),
+            gamma.apply(self.blue),
+        ]);
+    }
+}
+
+/// 8-bit HSV, with the hue ranging from `0` to `255`.
+///
+/// Converted with fast integer math, which makes it well suited for rainbow effects.
+/// The resulting RGB values get sent to the strip unmodified.
+impl Pixel for palette::Hsv<palette::encoding::Srgb, u8> {
+    const BYTES: usize = 3;
 
-    fn into_ws2812_bytes(self) -> Self::BytesIter {
-        [self.green, self.red, self.blue].into_iter()
+    fn write_bytes(&self, out: &mut [u8]) {
+        let [red, green, blue] = hsv_to_rgb(self.hue.into_inner(), self.saturation, self.value);
+        out.copy_from_slice(&[green, red, blue]);
     }
 }
 
-impl<'a, P> Pixel for &'a P
+/// Converts 8-bit HSV to 8-bit RGB, using integer math only.
+pub(crate) fn hsv_to_rgb(hue: u8, saturation: u8, value: u8) -> [u8; 3] {
+    if saturation == 0 {
+        return [value; 3];
+    }
+
+    // Split the hue circle into six sectors of 43 steps each
+    let sector = hue / 43;
+    let remainder = u16::from(hue - sector * 43) * 6;
+
+    let value_u16 = u16::from(value);
+    let saturation = u16::from(saturation);
+    let p = (value_u16 * (255 - saturation) / 255) as u8;
+    let q = (value_u16 * (255 - saturation * remainder / 255) / 255) as u8;
+    let t = (value_u16 * (255 - saturation * (255 - remainder) / 255) / 255) as u8;
+
+    match sector {
+        0 => [value, t, p],
+        1 => [q, value, p],
+        2 => [p, value, t],
+        3 => [p, q, value],
+        4 => [t, p, value],
+        _ => [value, p, q],
+    }
+}
+
+/// Packed `0x00RRGGBB` data.
+impl Pixel for u32 {
+    const BYTES: usize = 3;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        let [_, red, green, blue] = self.to_be_bytes();
+        out.copy_from_slice(&[green, red, blue]);
+    }
+}
+
+/// 8-bit RGB of the `embedded-graphics` crate.
+#[cfg(feature = "embedded-graphics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-graphics")))]
+impl Pixel for embedded_graphics_core::pixelcolor::Rgb888 {
+    const BYTES: usize = 3;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        use embedded_graphics_core::pixelcolor::RgbColor;
+        out.copy_from_slice(&[self.g(), self.r(), self.b()]);
+    }
+}
+
+/// 8-bit RGB of the `rgb` crate.
+///
+/// This is also the `RGB8` type of the `smart-leds` crate.
+#[cfg(feature = "rgb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rgb")))]
+impl Pixel for rgb::RGB8 {
+    const BYTES: usize = 3;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        out.copy_from_slice(&[self.g, self.r, self.b]);
+    }
+}
+
+/// 8-bit RGBW of the `smart-leds` crate.
+#[cfg(feature = "smart-leds")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smart-leds")))]
+impl Pixel for smart_leds_trait::RGBW<u8> {
+    const BYTES: usize = 4;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        out.copy_from_slice(&[self.g, self.r, self.b, self.a.0]);
+    }
+}
+
+impl<P> Pixel for &P
+where
+    P: Pixel,
+{
+    const BYTES: usize = P::BYTES;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        (*self).write_bytes(out)
+    }
+}
+
+/// An optional pixel; `None` is black.
+impl<P> Pixel for Option<P>
 where
-    P: Pixel + Clone,
+    P: Pixel,
 {
-    type BytesIter = <P as Pixel>::BytesIter;
-    fn into_ws2812_bytes(self) -> Self::BytesIter {
-        self.clone().into_ws2812_bytes()
+    const BYTES: usize = P::BYTES;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        match self {
+            Some(pixel) => pixel.write_bytes(out),
+            None => out.fill(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<P: Pixel>(pixel: P) -> [u8; 3] {
+        let mut out = [0; 3];
+        pixel.write_bytes(&mut out);
+        out
+    }
+
+    #[test]
+    fn hsv_sector_boundaries() {
+        assert_eq!(hsv_to_rgb(0, 255, 255), [255, 0, 0]);
+        assert_eq!(hsv_to_rgb(42, 255, 255), [255, 252, 0]);
+        assert_eq!(hsv_to_rgb(43, 255, 255), [255, 255, 0]);
+        assert_eq!(hsv_to_rgb(85, 255, 255), [3, 255, 0]);
+        assert_eq!(hsv_to_rgb(86, 255, 255), [0, 255, 0]);
+        assert_eq!(hsv_to_rgb(129, 255, 255), [0, 255, 255]);
+        assert_eq!(hsv_to_rgb(172, 255, 255), [0, 0, 255]);
+        assert_eq!(hsv_to_rgb(215, 255, 255), [255, 0, 255]);
+        assert_eq!(hsv_to_rgb(255, 255, 255), [255, 0, 15]);
+    }
+
+    #[test]
+    fn hsv_without_saturation_is_gray() {
+        for hue in [0, 42, 43, 255] {
+            assert_eq!(hsv_to_rgb(hue, 0, 100), [100; 3]);
+        }
+        assert_eq!(hsv_to_rgb(100, 255, 0), [0; 3]);
+    }
+
+    #[test]
+    fn hsv_sent_as_grb() {
+        let hsv = palette::Hsv::<palette::encoding::Srgb, u8>::new(43, 255, 255);
+        assert_eq!(bytes(hsv), [255, 255, 0]);
+        let hsv = palette::Hsv::<palette::encoding::Srgb, u8>::new(0, 255, 200);
+        assert_eq!(bytes(hsv), [0, 200, 0]);
+    }
+
+    #[test]
+    fn packed_u32_sent_as_grb() {
+        assert_eq!(bytes(0xFF_11_22_33_u32), [0x22, 0x11, 0x33]);
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-graphics")]
+    fn rgb888_sent_as_grb() {
+        let color = embedded_graphics_core::pixelcolor::Rgb888::new(1, 2, 3);
+        assert_eq!(bytes(color), [2, 1, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "rgb")]
+    fn rgb8_sent_as_grb() {
+        assert_eq!(bytes(rgb::RGB8::new(1, 2, 3)), [2, 1, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "smart-leds")]
+    fn smart_leds_rgbw_sent_as_grbw() {
+        let color = smart_leds_trait::RGBW {
+            r: 1,
+            g: 2,
+            b: 3,
+            a: smart_leds_trait::White(4),
+        };
+        let mut out = [0; 4];
+        color.write_bytes(&mut out);
+        assert_eq!(out, [2, 1, 3, 4]);
     }
 }
@@ -1,17 +1,34 @@
-use crate::pixel::Pixel;
+use core::{
+    iter::{Fuse, Map, Rev},
+    marker::PhantomData,
+};
 
+use crate::pixel::{Pixel, PixelSize, MAX_PIXEL_BYTES};
+
+/// A stream of raw bytes that get sent to a single LED strip.
+///
+/// Usually created through [`IntoPixelStream::into_pixel_stream()`]; can be
+/// modified with the combinators of [`PixelStreamExt`](crate::PixelStreamExt).
 pub trait PixelStreamRef {
+    /// Returns the next byte, or `None` if the stream is finished.
     fn next(&mut self) -> Option<u8>;
+
+    /// The number of bytes a single pixel of this stream consists of.
+    fn bytes_per_pixel(&self) -> usize;
 }
 
+/// A stream of the raw bytes of a sequence of pixels.
+///
+/// Created by [`IntoPixelStream::into_pixel_stream()`].
 pub struct PixelStream<P, I>
 where
     P: Pixel,
     I: Iterator<Item = P>,
 {
-    pixel_stream: I,
-    bytes_iter: Option<P::BytesIter>,
-    finished: bool,
+    pixel_stream: Fuse<I>,
+    bytes: [u8; MAX_PIXEL_BYTES],
+    pos: usize,
+    _pixel: PhantomData<fn() -> P>,
 }
 
 impl<I, P> PixelStream<P, I>
@@ -20,10 +37,60 @@ where
     I: Iterator<Item = P>,
 {
     fn new(pixel_stream: I) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = PixelSize::<P>::CHECK;
+
         Self {
-            pixel_stream,
-            bytes_iter: None,
-            finished: false,
+            pixel_stream: pixel_stream.fuse(),
+            bytes: [0; MAX_PIXEL_BYTES],
+            pos: P::BYTES,
+            _pixel: PhantomData,
+        }
+    }
+
+    /// Converts every pixel of the stream with the given function.
+    ///
+    /// Must be called before any data was read from the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pixel was only partially read.
+    pub fn map<Q, F>(self, f: F) -> PixelStream<Q, Map<Fuse<I>, F>>
+    where
+        Q: Pixel,
+        F: FnMut(P) -> Q,
+    {
+        assert!(self.pos == P::BYTES, "Pixel was only partially read");
+        PixelStream::new(self.pixel_stream.map(f))
+    }
+
+    /// Reverses the order of the pixels, for strips that are mounted backwards.
+    ///
+    /// Must be called before any data was read from the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pixel was only partially read.
+    pub fn reverse(self) -> PixelStream<P, Rev<Fuse<I>>>
+    where
+        I: DoubleEndedIterator,
+    {
+        assert!(self.pos == P::BYTES, "Pixel was only partially read");
+        PixelStream::new(self.pixel_stream.rev())
+    }
+}
+
+impl<I, P> Clone for PixelStream<P, I>
+where
+    P: Pixel,
+    I: Iterator<Item = P> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pixel_stream: self.pixel_stream.clone(),
+            bytes: self.bytes,
+            pos: self.pos,
+            _pixel: PhantomData,
         }
     }
 }
@@ -34,25 +101,20 @@ where
     I: Iterator<Item = P>,
 {
     fn next(&mut self) -> Option<u8> {
-        loop {
-            if self.finished {
-                return None;
-            }
-
-            if self.bytes_iter.is_none() {
-                self.bytes_iter = self.pixel_stream.next().map(|p| p.into_ws2812_bytes());
-            }
-
-            if let Some(bytes_iter) = self.bytes_iter.as_mut() {
-                if let Some(byte) = bytes_iter.next() {
-                    return Some(byte);
-                } else {
-                    self.bytes_iter = None;
-                }
-            } else {
-                self.finished = true;
-            }
+        if self.pos >= P::BYTES {
+            self.pixel_stream
+                .next()?
+                .write_bytes(&mut self.bytes[..P::BYTES]);
+            self.pos = 0;
         }
+
+        let byte = self.bytes[self.pos];
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        P::BYTES
     }
 }
 
@@ -79,3 +141,37 @@ where
         PixelStream::new(self.into_iter())
     }
 }
+
+/// Borrows an array of pixel streams as the trait objects the preparation functions expect.
+pub(crate) fn stream_refs<S: PixelStreamRef, const L: usize>(
+    streams: &mut [S; L],
+) -> [&mut dyn PixelStreamRef; L] {
+    let mut streams = streams.iter_mut();
+    core::array::from_fn(|_| streams.next().unwrap() as &mut dyn PixelStreamRef)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic = "Pixel was only partially read"]
+    fn reverse_after_partial_read() {
+        let mut stream = [[1u8, 2, 3]; 2].into_pixel_stream();
+        stream.next();
+        let _ = stream.reverse();
+    }
+
+    #[test]
+    fn map_and_reverse_before_reading() {
+        let mut stream = [[1u8, 2, 3], [4, 5, 6]]
+            .into_pixel_stream()
+            .map(|[r, g, b]| [b, g, r])
+            .reverse();
+
+        // `[u8; 3]` pixels are RGB, they get transmitted as GRB
+        let bytes: [_; 6] = core::array::from_fn(|_| stream.next().unwrap());
+        assert_eq!(bytes, [5, 6, 4, 2, 3, 1]);
+        assert_eq!(stream.next(), None);
+    }
+}
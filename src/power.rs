@@ -0,0 +1,45 @@
+use crate::{brightness::Brightness, pixel::MAX_PIXEL_BYTES};
+
+/// The current draw of an LED strip, used to estimate the current of a frame.
+///
+/// All currents are given in microamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerModel {
+    /// The current of each color channel of an LED at full duty.
+    ///
+    /// The channels are in the order they get sent to the strip;
+    /// for example GRB for WS2812 strips.
+    pub channel_microamps: [u32; MAX_PIXEL_BYTES],
+    /// The current of a single LED while all of its channels are off.
+    pub idle_microamps: u32,
+}
+
+impl PowerModel {
+    /// A typical WS2812 LED, with 20 mA per channel and 1 mA idle current.
+    pub const WS2812: Self = Self::uniform(20_000, 1_000);
+
+    /// Creates a model where all color channels draw the same current.
+    pub const fn uniform(channel_microamps: u32, idle_microamps: u32) -> Self {
+        Self {
+            channel_microamps: [channel_microamps; MAX_PIXEL_BYTES],
+            idle_microamps,
+        }
+    }
+}
+
+impl Default for PowerModel {
+    fn default() -> Self {
+        Self::WS2812
+    }
+}
+
+/// The result of [`PreprocessedPixels::limit_current()`](crate::PreprocessedPixels::limit_current).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerReport {
+    /// The estimated current of the frame before limiting, in milliamps.
+    pub estimated_milliamps: u32,
+    /// The brightness the frame was scaled with to stay within the budget.
+    pub scaling: Brightness,
+    /// The estimated current of the frame after limiting, in milliamps.
+    pub limited_milliamps: u32,
+}
@@ -0,0 +1,100 @@
+use palette::LinSrgb;
+
+use crate::pixel::Pixel;
+
+/// A 16-bit per channel RGB pixel, as used by WS2816 strips.
+///
+/// Gets sent as six bytes: GRB, with the most significant byte of each channel first.
+///
+/// # Timing
+///
+/// The driver always transmits with the WS2812 timing of 800 kHz, which
+/// WS2816 strips are compatible with. Due to the six bytes per pixel, the
+/// transmission of a frame takes twice as long as with an 8-bit RGB strip of the
+/// same length; the buffer has to be sized with `P = 6`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb16 {
+    /// The red channel.
+    pub red: u16,
+    /// The green channel.
+    pub green: u16,
+    /// The blue channel.
+    pub blue: u16,
+}
+
+impl Rgb16 {
+    /// Creates a new pixel from its raw channel values.
+    pub const fn new(red: u16, green: u16, blue: u16) -> Self {
+        Self { red, green, blue }
+    }
+}
+
+impl From<LinSrgb<u16>> for Rgb16 {
+    fn from(color: LinSrgb<u16>) -> Self {
+        Self::new(color.red, color.green, color.blue)
+    }
+}
+
+impl From<&LinSrgb<u16>> for Rgb16 {
+    fn from(color: &LinSrgb<u16>) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl From<LinSrgb<f32>> for Rgb16 {
+    fn from(color: LinSrgb<f32>) -> Self {
+        Self::from(color.into_format::<u16>())
+    }
+}
+
+impl From<&LinSrgb<f32>> for Rgb16 {
+    fn from(color: &LinSrgb<f32>) -> Self {
+        Self::from(*color)
+    }
+}
+
+/// Expands an 8-bit color to the full 16-bit range.
+impl From<LinSrgb<u8>> for Rgb16 {
+    fn from(color: LinSrgb<u8>) -> Self {
+        Self::from(color.into_format::<u16>())
+    }
+}
+
+impl From<&LinSrgb<u8>> for Rgb16 {
+    fn from(color: &LinSrgb<u8>) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl Pixel for Rgb16 {
+    const BYTES: usize = 6;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        let [green_high, green_low] = self.green.to_be_bytes();
+        let [red_high, red_low] = self.red.to_be_bytes();
+        let [blue_high, blue_low] = self.blue.to_be_bytes();
+        out.copy_from_slice(&[
+            green_high, green_low, red_high, red_low, blue_high, blue_low,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sent_as_big_endian_grb() {
+        let mut out = [0; 6];
+        Rgb16::new(0x0102, 0x0304, 0x0506).write_bytes(&mut out);
+        assert_eq!(out, [0x03, 0x04, 0x01, 0x02, 0x05, 0x06]);
+    }
+
+    #[test]
+    fn expands_8_bit_colors() {
+        assert_eq!(
+            Rgb16::from(LinSrgb::new(0u8, 1, 255)),
+            Rgb16::new(0, 0x0101, 0xFFFF)
+        );
+    }
+}
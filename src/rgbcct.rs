@@ -0,0 +1,112 @@
+use palette::LinSrgb;
+
+use crate::pixel::Pixel;
+
+/// An RGB pixel with an additional warm white and cool white channel,
+/// as used by WS2805 RGBCCT strips.
+///
+/// Gets sent as five bytes: GRB, followed by cool white and warm white.
+/// For strips with a different RGB order, wrap it in [`Reordered`](crate::Reordered).
+///
+/// # Timing
+///
+/// The driver always transmits with the WS2812 timing of 800 kHz, which
+/// WS2805 strips are compatible with. Due to the five bytes per pixel, the
+/// transmission of a frame takes 5/3 as long as with an RGB strip of the
+/// same length; the buffer has to be sized with `P = 5`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgbcct {
+    /// The red channel.
+    pub red: u8,
+    /// The green channel.
+    pub green: u8,
+    /// The blue channel.
+    pub blue: u8,
+    /// The warm white channel.
+    pub warm_white: u8,
+    /// The cool white channel.
+    pub cool_white: u8,
+}
+
+impl Rgbcct {
+    /// Creates a new pixel from its raw channel values.
+    pub const fn new(red: u8, green: u8, blue: u8, warm_white: u8, cool_white: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            warm_white,
+            cool_white,
+        }
+    }
+
+    /// Creates a new pixel from an RGB color and a white component.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color of the RGB LEDs
+    /// * `white` - The brightness of the white LEDs
+    /// * `coolness` - The mix of the white LEDs, from `0` (warm) to `255` (cool)
+    pub fn with_white(color: LinSrgb<u8>, white: u8, coolness: u8) -> Self {
+        let cool_white = (u16::from(white) * u16::from(coolness) / 255) as u8;
+        let warm_white = white - cool_white;
+
+        Self::new(color.red, color.green, color.blue, warm_white, cool_white)
+    }
+}
+
+/// Converts an RGB color, with the white LEDs turned off.
+impl From<LinSrgb<u8>> for Rgbcct {
+    fn from(color: LinSrgb<u8>) -> Self {
+        Self::new(color.red, color.green, color.blue, 0, 0)
+    }
+}
+
+impl From<&LinSrgb<u8>> for Rgbcct {
+    fn from(color: &LinSrgb<u8>) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl Pixel for Rgbcct {
+    const BYTES: usize = 5;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        out.copy_from_slice(&[
+            self.green,
+            self.red,
+            self.blue,
+            self.cool_white,
+            self.warm_white,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sent_as_grb_cool_warm() {
+        let mut out = [0; 5];
+        Rgbcct::new(1, 2, 3, 4, 5).write_bytes(&mut out);
+        assert_eq!(out, [2, 1, 3, 5, 4]);
+    }
+
+    #[test]
+    fn white_gets_split_by_coolness() {
+        let color = LinSrgb::new(1, 2, 3);
+        assert_eq!(
+            Rgbcct::with_white(color, 200, 0),
+            Rgbcct::new(1, 2, 3, 200, 0)
+        );
+        assert_eq!(
+            Rgbcct::with_white(color, 200, 255),
+            Rgbcct::new(1, 2, 3, 0, 200)
+        );
+        assert_eq!(
+            Rgbcct::with_white(color, 200, 128),
+            Rgbcct::new(1, 2, 3, 100, 100)
+        );
+    }
+}
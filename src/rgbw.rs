@@ -0,0 +1,278 @@
+use palette::LinSrgb;
+
+use crate::pixel::Pixel;
+
+/// The color of the white LED of an RGBW strip, in 8-bit linear sRGB.
+///
+/// Describes which mix of the red, green and blue LEDs produces the same
+/// color as the white LED at full brightness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitePoint {
+    /// The red component of the white LED.
+    pub red: u8,
+    /// The green component of the white LED.
+    pub green: u8,
+    /// The blue component of the white LED.
+    pub blue: u8,
+}
+
+/// The linear sRGB colors of a black body, normalized to the brightest channel.
+///
+/// From 1000 K to 12000 K, in steps of 500 K.
+/// Tanner Helland's approximation of the black body curve produces gamma-encoded
+/// sRGB values; they were converted to linear sRGB with the sRGB transfer function
+/// and normalized again. For example, 3000 K is `(255, 177, 110)` encoded
+/// and `(255, 112, 40)` linear.
+const BLACK_BODY: [[u8; 3]; 23] = [
+    [255, 15, 0],
+    [255, 38, 0],
+    [255, 64, 1],
+    [255, 88, 16],
+    [255, 112, 40],
+    [255, 135, 68],
+    [255, 157, 97],
+    [255, 178, 127],
+    [255, 198, 157],
+    [255, 217, 187],
+    [255, 235, 216],
+    [255, 253, 244],
+    [228, 227, 255],
+    [201, 212, 255],
+    [185, 201, 255],
+    [173, 194, 255],
+    [164, 188, 255],
+    [156, 183, 255],
+    [150, 179, 255],
+    [145, 175, 255],
+    [140, 172, 255],
+    [137, 169, 255],
+    [133, 167, 255],
+];
+
+impl WhitePoint {
+    /// A white LED that matches the full white of the RGB LEDs.
+    ///
+    /// With this white point, the white channel gets extracted via plain min-subtraction.
+    pub const NEUTRAL: Self = Self::new(255, 255, 255);
+    /// An approximation of a typical warm white LED, at around 3000 K.
+    pub const WARM_3000K: Self = Self::from_kelvin(3000);
+    /// An approximation of a typical neutral white LED, at around 4000 K.
+    pub const NEUTRAL_4000K: Self = Self::from_kelvin(4000);
+    /// An approximation of a typical cool white LED, at around 6500 K.
+    pub const COOL_6500K: Self = Self::from_kelvin(6500);
+
+    /// Creates a new white point from the color of the white LED.
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Approximates the color of a white light source with the given color temperature.
+    ///
+    /// Supports temperatures from 1000 K to 12000 K; values outside of
+    /// this range get clamped.
+    pub const fn from_kelvin(kelvin: u16) -> Self {
+        const MIN: u16 = 1000;
+        const STEP: u16 = 500;
+
+        let offset = kelvin.saturating_sub(MIN);
+        let index = (offset / STEP) as usize;
+        if index >= BLACK_BODY.len() - 1 {
+            let [red, green, blue] = BLACK_BODY[BLACK_BODY.len() - 1];
+            return Self::new(red, green, blue);
+        }
+
+        let fraction = (offset % STEP) as i32;
+        let lower = BLACK_BODY[index];
+        let upper = BLACK_BODY[index + 1];
+
+        let mut result = [0; 3];
+        let mut channel = 0;
+        while channel < 3 {
+            let low = lower[channel] as i32;
+            let high = upper[channel] as i32;
+            result[channel] = (low + (high - low) * fraction / STEP as i32) as u8;
+            channel += 1;
+        }
+
+        Self::new(result[0], result[1], result[2])
+    }
+}
+
+impl Default for WhitePoint {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+/// An RGB color that gets sent to an RGBW strip, like the SK6812 RGBW.
+///
+/// The white channel gets derived automatically from the RGB color:
+/// As much of the color as possible is produced by the white LED, and the
+/// RGB LEDs only produce the remainder. The color of the white LED is
+/// described by a [`WhitePoint`].
+///
+/// Gets sent in GRBW order.
+///
+/// This allows effects that were written for RGB strips to run on RGBW strips unchanged:
+///
+/// ```rust
+/// buffer.prepare_pixels([&mut framebuffer.iter().map(Rgbw::from).into_pixel_stream()]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgbw {
+    color: LinSrgb<u8>,
+    white_point: WhitePoint,
+}
+
+impl Rgbw {
+    /// Creates an RGBW pixel whose white channel gets extracted via min-subtraction.
+    pub const fn new(color: LinSrgb<u8>) -> Self {
+        Self::with_white_point(color, WhitePoint::NEUTRAL)
+    }
+
+    /// Creates an RGBW pixel for a white LED of the given color.
+    pub const fn with_white_point(color: LinSrgb<u8>, white_point: WhitePoint) -> Self {
+        Self { color, white_point }
+    }
+
+    /// Splits the color into its RGB and its white part.
+    fn split(&self) -> ([u8; 3], u8) {
+        let color = [self.color.red, self.color.green, self.color.blue];
+        let white_point = [
+            self.white_point.red,
+            self.white_point.green,
+            self.white_point.blue,
+        ];
+
+        // The highest white value that doesn't exceed any of the channels
+        let white = color
+            .iter()
+            .zip(white_point)
+            .filter(|(_, w)| *w != 0)
+            .map(|(&c, w)| u16::from(c) * 255 / u16::from(w))
+            .min()
+            .unwrap_or(0)
+            .min(255);
+
+        let mut rgb = color;
+        for (c, w) in rgb.iter_mut().zip(white_point) {
+            *c = c.saturating_sub((white * u16::from(w) / 255) as u8);
+        }
+
+        (rgb, white as u8)
+    }
+}
+
+impl From<LinSrgb<u8>> for Rgbw {
+    fn from(color: LinSrgb<u8>) -> Self {
+        Self::new(color)
+    }
+}
+
+impl From<&LinSrgb<u8>> for Rgbw {
+    fn from(color: &LinSrgb<u8>) -> Self {
+        Self::new(*color)
+    }
+}
+
+impl Pixel for Rgbw {
+    const BYTES: usize = 4;
+
+    fn write_bytes(&self, out: &mut [u8]) {
+        let ([red, green, blue], white) = self.split();
+        out.copy_from_slice(&[green, red, blue, white]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(pixel: Rgbw) -> [u8; 4] {
+        let mut out = [0; 4];
+        pixel.write_bytes(&mut out);
+        out
+    }
+
+    #[test]
+    fn sent_as_grbw() {
+        // Red, green and blue without a common part don't produce any white
+        assert_eq!(bytes(Rgbw::new(LinSrgb::new(10, 0, 30))), [0, 10, 30, 0]);
+        assert_eq!(bytes(Rgbw::new(LinSrgb::new(0, 20, 0))), [20, 0, 0, 0]);
+    }
+
+    #[test]
+    fn neutral_white_extraction() {
+        assert_eq!(
+            bytes(Rgbw::new(LinSrgb::new(255, 255, 255))),
+            [0, 0, 0, 255]
+        );
+        assert_eq!(bytes(Rgbw::new(LinSrgb::new(50, 80, 30))), [50, 20, 0, 30]);
+        assert_eq!(bytes(Rgbw::new(LinSrgb::new(0, 0, 0))), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn colored_white_extraction() {
+        let white_point = WhitePoint::new(255, 128, 0);
+
+        // Exactly the color of the white LED
+        let pixel = Rgbw::with_white_point(LinSrgb::new(255, 128, 0), white_point);
+        assert_eq!(bytes(pixel), [0, 0, 0, 255]);
+
+        // The white LED is limited by the green channel
+        let pixel = Rgbw::with_white_point(LinSrgb::new(200, 64, 10), white_point);
+        assert_eq!(bytes(pixel), [1, 73, 10, 127]);
+    }
+
+    #[test]
+    fn from_kelvin_interpolates() {
+        assert_eq!(WhitePoint::from_kelvin(3000), WhitePoint::new(255, 112, 40));
+        assert_eq!(WhitePoint::from_kelvin(3250), WhitePoint::new(255, 123, 54));
+        assert_eq!(
+            WhitePoint::from_kelvin(6750),
+            WhitePoint::new(242, 240, 249)
+        );
+        assert_eq!(WhitePoint::WARM_3000K, WhitePoint::from_kelvin(3000));
+        assert_eq!(WhitePoint::NEUTRAL_4000K, WhitePoint::from_kelvin(4000));
+        assert_eq!(WhitePoint::COOL_6500K, WhitePoint::from_kelvin(6500));
+    }
+
+    #[test]
+    fn from_kelvin_clamps() {
+        assert_eq!(WhitePoint::from_kelvin(0), WhitePoint::from_kelvin(1000));
+        assert_eq!(
+            WhitePoint::from_kelvin(12000),
+            WhitePoint::new(133, 167, 255)
+        );
+        assert_eq!(
+            WhitePoint::from_kelvin(u16::MAX),
+            WhitePoint::from_kelvin(12000)
+        );
+    }
+
+    #[test]
+    fn black_body_table_is_linear() {
+        // Tanner Helland's gamma-encoded values, converted with the sRGB transfer function
+        for (kelvin, encoded) in [
+            (2000, [255, 137, 14]),
+            (3000, [255, 177, 110]),
+            (6500, [255, 254, 250]),
+            (10000, [202, 218, 255]),
+        ] {
+            let linear = encoded.map(|c| crate::SRGB_GAMMA.apply(c));
+            let WhitePoint { red, green, blue } = WhitePoint::from_kelvin(kelvin);
+            for (expected, actual) in linear.into_iter().zip([red, green, blue]) {
+                assert!(expected.abs_diff(actual) <= 1, "{kelvin}: {linear:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn warm_white_extraction() {
+        let pixel = Rgbw::with_white_point(LinSrgb::new(255, 112, 40), WhitePoint::WARM_3000K);
+        assert_eq!(bytes(pixel), [0, 0, 0, 255]);
+
+        let pixel = Rgbw::with_white_point(LinSrgb::new(255, 255, 255), WhitePoint::WARM_3000K);
+        assert_eq!(bytes(pixel), [143, 0, 215, 255]);
+    }
+}
@@ -0,0 +1,207 @@
+use core::convert::Infallible;
+
+use imxrt_ral::{flexio, Valid};
+use smart_leds_trait::{SmartLedsWrite, SmartLedsWriteAsync, RGB8};
+
+use crate::{
+    flexio::{PreprocessedPixels, WS2812Driver},
+    pins::Pins,
+    pixelstream::IntoPixelStream,
+};
+
+/// Blocking `smart-leds` support for single-strip drivers.
+///
+/// The driver itself can't implement [`SmartLedsWriteAsync`]: its async
+/// transmission requires a DMA channel, the DMA signal id and a
+/// [`PreprocessedPixels`] buffer, none of which are owned by the driver.
+/// For async writes, as well as for drivers with multiple strips, use [`DmaWriter`].
+impl<const N: u8, PINS: Pins<N, 1>> SmartLedsWrite for WS2812Driver<N, 1, PINS>
+where
+    flexio::Instance<N>: Valid,
+{
+    type Error = Infallible;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        let mut pixels = iterator.into_iter().map(Into::into).into_pixel_stream();
+        WS2812Driver::write(self, [&mut pixels]);
+        Ok(())
+    }
+}
+
+/// A DMA based `smart-leds` writer, supporting both the blocking and the async API.
+///
+/// Every write prepares the pixels of the selected LED strip in the buffer and
+/// transmits the entire buffer; the other LED strips keep the data that was
+/// previously prepared for them. This allows multiple effects that were written
+/// for `smart-leds` to run on different strips of the same driver.
+///
+/// ```rust
+/// let mut writer = DmaWriter::new(&mut neopixel, &mut buffer, &mut dma, DMA_MUX_SRC);
+///
+/// SmartLedsWriteAsync::write(&mut writer, effect.iter().copied()).await.unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "smart-leds")))]
+pub struct DmaWriter<'a, const N: u8, const L: usize, PINS, const N2: usize, const P: usize>
+where
+    PINS: Pins<N, L>,
+    flexio::Instance<N>: Valid,
+{
+    driver: &'a mut WS2812Driver<N, L, PINS>,
+    buffer: &'a mut PreprocessedPixels<N2, L, P>,
+    dma: &'a mut imxrt_dma::channel::Channel,
+    dma_signal_id: u32,
+    lane: usize,
+}
+
+impl<'a, const N: u8, const L: usize, PINS, const N2: usize, const P: usize>
+    DmaWriter<'a, N, L, PINS, N2, P>
+where
+    PINS: Pins<N, L>,
+    flexio::Instance<N>: Valid,
+{
+    /// Creates a writer for the first LED strip.
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - The driver of the LED strips
+    /// * `buffer` - The buffer the pixels get prepared in
+    /// * `dma` - The dma channel that should be used to transmit the data
+    /// * `dma_signal_id` - The signal the FlexIO unit uses to communicate with the DMA.
+    ///   See [`WS2812Driver::write_dma()`].
+    pub fn new(
+        driver: &'a mut WS2812Driver<N, L, PINS>,
+        buffer: &'a mut PreprocessedPixels<N2, L, P>,
+        dma: &'a mut imxrt_dma::channel::Channel,
+        dma_signal_id: u32,
+    ) -> Self {
+        Self {
+            driver,
+            buffer,
+            dma,
+            dma_signal_id,
+            lane: 0,
+        }
+    }
+
+    /// Selects the LED strip that gets written to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lane` is not smaller than `L`.
+    pub fn lane(self, lane: usize) -> Self {
+        assert!(lane < L, "Strip index out of range");
+        Self { lane, ..self }
+    }
+
+    /// Prepares the pixels of the selected LED strip.
+    fn prepare<T, I>(&mut self, iterator: T)
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<RGB8>,
+    {
+        prepare_lane(self.buffer, self.lane, iterator);
+    }
+}
+
+/// Prepares `smart-leds` pixels in a single lane of the buffer,
+/// leaving the other lanes untouched.
+fn prepare_lane<T, I, const N: usize, const L: usize, const P: usize>(
+    buffer: &mut PreprocessedPixels<N, L, P>,
+    lane: usize,
+    iterator: T,
+) where
+    T: IntoIterator<Item = I>,
+    I: Into<RGB8>,
+{
+    buffer.prepare_strip(
+        lane,
+        &mut iterator.into_iter().map(Into::into).into_pixel_stream(),
+    );
+}
+
+impl<const N: u8, const L: usize, PINS, const N2: usize, const P: usize> SmartLedsWrite
+    for DmaWriter<'_, N, L, PINS, N2, P>
+where
+    PINS: Pins<N, L>,
+    flexio::Instance<N>: Valid,
+{
+    type Error = imxrt_dma::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.prepare(iterator);
+        self.driver
+            .write_dma_blocking(self.buffer, self.dma, self.dma_signal_id, || ())?;
+        Ok(())
+    }
+}
+
+impl<const N: u8, const L: usize, PINS, const N2: usize, const P: usize> SmartLedsWriteAsync
+    for DmaWriter<'_, N, L, PINS, N2, P>
+where
+    PINS: Pins<N, L>,
+    flexio::Instance<N>: Valid,
+{
+    type Error = imxrt_dma::Error;
+    type Color = RGB8;
+
+    async fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.prepare(iterator);
+        self.driver
+            .write_dma(self.buffer, self.dma, self.dma_signal_id, async {})
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: [RGB8; 3] = [RGB8::new(1, 2, 3), RGB8::new(4, 5, 6), RGB8::new(7, 8, 9)];
+    const SECOND: [(u8, u8, u8); 2] = [(10, 11, 12), (13, 14, 15)];
+
+    #[test]
+    fn prepare_lane_matches_prepare_pixels() {
+        let mut written = PreprocessedPixels::<8, 3>::new();
+        prepare_lane(&mut written, 0, FIRST);
+        prepare_lane(&mut written, 2, SECOND);
+
+        let mut expected = PreprocessedPixels::<8, 3>::new();
+        expected.prepare_pixels([
+            &mut FIRST.into_pixel_stream(),
+            &mut [RGB8::default(); 0].into_pixel_stream(),
+            &mut SECOND.map(RGB8::from).into_pixel_stream(),
+        ]);
+
+        assert_eq!(written.get_dma_data(), expected.get_dma_data());
+    }
+
+    #[test]
+    fn prepare_lane_replaces_previous_data() {
+        let mut written = PreprocessedPixels::<8, 2>::new();
+        prepare_lane(&mut written, 1, FIRST);
+        prepare_lane(&mut written, 1, SECOND);
+
+        let mut expected = PreprocessedPixels::<8, 2>::new();
+        expected.prepare_pixels([
+            &mut [RGB8::default(); 0].into_pixel_stream(),
+            &mut SECOND.map(RGB8::from).into_pixel_stream(),
+        ]);
+
+        assert_eq!(written.get_dma_data(), expected.get_dma_data());
+    }
+}
@@ -0,0 +1,186 @@
+use core::{cell::RefCell, ops::Range};
+
+use crate::{
+    flexio::{PrepareSummary, PreprocessedPixels},
+    pixel::Pixel,
+    pixelstream::{stream_refs, IntoPixelStream},
+};
+
+/// A named range of pixels on a single LED strip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Zone<'a> {
+    /// The name of the zone.
+    pub name: &'a str,
+    /// The index of the LED strip.
+    pub strip: usize,
+    /// The positions of the pixels on the LED strip.
+    pub range: Range<usize>,
+}
+
+impl<'a> Zone<'a> {
+    /// Creates a new zone.
+    pub const fn new(name: &'a str, strip: usize, range: Range<usize>) -> Self {
+        Self { name, strip, range }
+    }
+
+    /// The number of pixels of the zone.
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Whether the zone doesn't contain any pixels.
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+}
+
+/// A set of zones that get rendered by independent effects.
+///
+/// Each zone is driven by its own effect, which gets called with the position
+/// of the pixel inside of the zone. Pixels that aren't part of any zone are
+/// black; if zones overlap, the zone that was registered last wins.
+///
+/// ```rust
+/// const ZONES: Zones<2> = Zones::new([
+///     Zone::new("left wing", 0, 0..120),
+///     Zone::new("right wing", 2, 40..200),
+/// ]);
+///
+/// ZONES.render(
+///     &mut buffer,
+///     [
+///         &mut |pos| rainbow(pos, t),
+///         &mut |_| LinSrgb::new(255, 0, 0),
+///     ],
+/// );
+/// ```
+///
+/// # Generics:
+///
+/// * `Z` - the number of zones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zones<'a, const Z: usize> {
+    zones: [Zone<'a>; Z],
+}
+
+impl<'a, const Z: usize> Zones<'a, Z> {
+    /// Creates a zone registry from its zones.
+    pub const fn new(zones: [Zone<'a>; Z]) -> Self {
+        Self { zones }
+    }
+
+    /// The registered zones.
+    pub fn zones(&self) -> &[Zone<'a>; Z] {
+        &self.zones
+    }
+
+    /// Finds the index of the zone with the given name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.zones.iter().position(|zone| zone.name == name)
+    }
+
+    /// The number of pixels of the given LED strip that are covered by zones.
+    pub fn strip_len(&self, strip: usize) -> usize {
+        self.zones
+            .iter()
+            .filter(|zone| zone.strip == strip)
+            .map(|zone| zone.range.end)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Renders all zones into a preprocessed pixel buffer, in a single pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer to render into
+    /// * `effects` - The effect of every zone, in the order of the zones.
+    ///   Gets called with the position of the pixel inside of the zone.
+    pub fn render<const N: usize, const L: usize, const P: usize, Px: Pixel>(
+        &self,
+        buffer: &mut PreprocessedPixels<N, L, P>,
+        effects: [&mut dyn FnMut(usize) -> Px; Z],
+    ) -> PrepareSummary<L> {
+        let effects = RefCell::new(effects);
+
+        let mut streams: [_; L] = core::array::from_fn(|strip| {
+            ZonePixels {
+                zones: self,
+                effects: &effects,
+                strip,
+                index: 0,
+                len: self.strip_len(strip),
+            }
+            .into_pixel_stream()
+        });
+        buffer.prepare_pixels(stream_refs(&mut streams))
+    }
+}
+
+/// The pixels of all zones of a single LED strip.
+///
+/// Yields `None` for pixels that aren't part of any zone.
+struct ZonePixels<'z, 'a, 'e, Px, const Z: usize> {
+    zones: &'z Zones<'a, Z>,
+    effects: &'z RefCell<[&'e mut dyn FnMut(usize) -> Px; Z]>,
+    strip: usize,
+    index: usize,
+    len: usize,
+}
+
+impl<Px, const Z: usize> Iterator for ZonePixels<'_, '_, '_, Px, Z> {
+    type Item = Option<Px>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let pixel = self
+            .zones
+            .zones
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, zone)| zone.strip == self.strip && zone.range.contains(&self.index))
+            .map(|(id, zone)| (self.effects.borrow_mut()[id])(self.index - zone.range.start));
+
+        self.index += 1;
+        Some(pixel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZONES: Zones<3> = Zones::new([
+        Zone::new("a", 0, 0..3),
+        Zone::new("b", 0, 2..4),
+        Zone::new("c", 1, 1..2),
+    ]);
+
+    #[test]
+    fn later_zones_win() {
+        let mut buffer = PreprocessedPixels::<8, 2>::new();
+
+        let summary = ZONES.render(
+            &mut buffer,
+            [
+                &mut |pos| 10 + pos as u32,
+                &mut |pos| 20 + pos as u32,
+                &mut |pos| 30 + pos as u32,
+            ],
+        );
+
+        assert_eq!(summary.strip_lengths, [12, 6]);
+
+        let mut expected = PreprocessedPixels::<8, 2>::new();
+        expected.prepare_pixels([
+            &mut [10u32, 11, 20, 21].into_pixel_stream(),
+            &mut [0u32, 30].into_pixel_stream(),
+        ]);
+        assert_eq!(buffer.get_dma_data(), expected.get_dma_data());
+        assert_eq!(ZONES.find("c"), Some(2));
+    }
+}